@@ -23,13 +23,56 @@ export interface KeyframeRule {
   map?: string
 }
 
-export declare function transform(filename: string, sourceCode: string, themeJson?: string | undefined | null, dir?: string | undefined | null): TransformResult
+/**
+ * Import, re-export and `export * from` specifiers of a module, excluding
+ * `taikocss/css` and type-only imports. The Vite plugin resolves these and
+ * passes the results back to `transform` as its module map.
+ */
+export declare function collectImports(filename: string, sourceCode: string): Array<string>
+
+export interface RecipeTypes {
+  /** The variable the recipe is assigned to, if any */
+  name?: string
+  /**
+   * TypeScript declaration of the recipe's variant props, e.g.
+   * `export type ButtonVariants = { size?: "sm" | "lg" }`
+   */
+  types: string
+}
+
+export interface ModuleSource {
+  code: string
+  /**
+   * Import specifier → resolved module id, for the specifiers of `code`
+   * that were resolved
+   */
+  imports: Record<string, string>
+}
+
+/**
+ * `modules` maps resolved module ids to their source and resolved imports.
+ * The entry for `filename` itself supplies the transformed file's import
+ * table; without a map, imported values cannot be evaluated.
+ *
+ * `defines` maps member chains (`process.env.NODE_ENV`, `import.meta.env`)
+ * to JSON values, like esbuild's `define`.
+ *
+ * `helpers` registers project helper functions for style values as
+ * templates: `{ clampType: "var(--font-size-{0})" }`.
+ */
+export declare function transform(filename: string, sourceCode: string, themeJson?: string | undefined | null, dir?: string | undefined | null, modules?: Record<string, ModuleSource> | undefined | null, defines?: Record<string, string> | undefined | null, helpers?: Record<string, string> | undefined | null, targets?: Record<string, string> | undefined | null): TransformResult
 
 export interface TransformResult {
   code: string
   cssRules: Array<ExtractedCssRule>
   globalCss: Array<GlobalCssRule>
   keyframes: Array<KeyframeRule>
+  recipes: Array<RecipeTypes>
   /** V3 source map JSON for the transformed JS. */
   map?: string
+  /**
+   * Ids of the modules whose values were folded into this file's CSS, so
+   * the bundler can re-transform it when one of them changes.
+   */
+  dependencies: Array<string>
 }
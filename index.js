@@ -577,3 +577,4 @@ if (!nativeBinding) {
 
 module.exports = nativeBinding
 module.exports.transform = nativeBinding.transform
+module.exports.collectImports = nativeBinding.collectImports
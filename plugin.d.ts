@@ -9,6 +9,33 @@ export interface TaikoOptions {
    */
   theme?: Theme
 
+  /**
+   * Build-time constants for `css()` values, like esbuild's `define`: a
+   * member chain mapped to a JSON string. Vite's `define` entries,
+   * `import.meta.env` and `process.env.NODE_ENV` are included automatically;
+   * these take precedence.
+   *
+   * @example { 'process.env.BRAND': '"acme"' }
+   */
+  defines?: Record<string, string>
+
+  /**
+   * Project helper functions usable inside `css()` values, expanded at build
+   * time. Each is a template whose `{0}`, `{1}`, … slots receive the call's
+   * arguments; the highest slot fixes the arity. Declare the functions for
+   * TypeScript and the runtime shim yourself.
+   *
+   * @example { clampType: 'var(--font-size-{0})' }
+   */
+  helpers?: Record<string, string>
+
+  /**
+   * Most local modules evaluated for one transformed file, counting the
+   * modules its imports reach. Exceeding it is a build error.
+   * @default 200
+   */
+  maxModules?: number
+
   css?: {
     /**
      * Text direction for the generated CSS.
@@ -21,6 +48,16 @@ export interface TaikoOptions {
      * @default false
      */
     generateForBothDir?: boolean
+
+    /**
+     * Minimum browser versions the generated CSS must work in, replacing the
+     * defaults (Chrome 105, Safari 16, Firefox 110). Browsers: `android`,
+     * `chrome`, `edge`, `firefox`, `ie`, `ios_saf`, `opera`, `safari`,
+     * `samsung`.
+     *
+     * @example { chrome: 111, safari: '16.2', firefox: 113 }
+     */
+    targets?: Record<string, string | number>
   }
 }
 
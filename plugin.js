@@ -1,4 +1,5 @@
 import { createRequire } from 'module'
+import { readFile, stat } from 'fs/promises'
 
 const require = createRequire(import.meta.url)
 
@@ -10,13 +11,134 @@ function loadNative() {
   }
 }
 
-const { transform } = loadNative()
+const { transform, collectImports } = loadNative()
 
 // Store the actual CSS contents
 const cssMap = new Map()
 // Store a version counter to bust the browser cache safely
 const cssVersions = new Map()
 
+// Modules whose source the Rust transform can evaluate across imports
+const EVALUATABLE_RE = /\.(m|c)?(t|j)sx?$|\.json$/
+// Default upper bound on the import graph walked for one transformed file
+const MAX_MODULES = 200
+
+// Walk the import graph of `id` with the bundler's resolver, returning the
+// module map `transform` evaluates imported values from:
+// resolved id → { code, imports: { specifier → resolved id } }.
+// Imported modules come from `cache` while their mtime is unchanged, so a
+// transform or hot update only re-reads and re-resolves the files that changed.
+async function collectModules(ctx, id, code, cache, maxModules) {
+  const modules = { [id]: { code, imports: await resolveImports(ctx, id, code) } }
+  const queue = Object.values(modules[id].imports)
+  const seen = new Set([id, ...queue])
+  while (queue.length > 0) {
+    const file = queue.shift()
+    const module = await loadModule(ctx, file, cache)
+    if (!module) continue
+    if (Object.keys(modules).length >= maxModules) {
+      throw new Error(
+        `taikocss: the imports of ${id} reach more than ${maxModules} local modules. ` +
+        `Raise the \`maxModules\` option, or import tokens from modules with fewer imports.`
+      )
+    }
+    modules[file] = { code: module.code, imports: module.imports }
+    for (const dep of Object.values(module.imports)) {
+      if (seen.has(dep)) continue
+      seen.add(dep)
+      queue.push(dep)
+    }
+  }
+  return modules
+}
+
+// One imported module's source and resolved imports, cached by path + mtime.
+// Unreadable files are skipped.
+async function loadModule(ctx, file, cache) {
+  let mtimeMs
+  try {
+    ({ mtimeMs } = await stat(file))
+  } catch {
+    return null
+  }
+  const cached = cache.get(file)
+  if (cached?.mtimeMs === mtimeMs) return cached
+
+  let code
+  try {
+    code = await readFile(file, 'utf8')
+  } catch {
+    return null
+  }
+  const imports = file.endsWith('.json') ? {} : await resolveImports(ctx, file, code)
+  const module = { mtimeMs, code, imports }
+  cache.set(file, module)
+  return module
+}
+
+// The imports of `importer` the transform can evaluate: specifier → resolved id
+async function resolveImports(ctx, importer, code) {
+  const imports = {}
+  for (const specifier of collectImports(importer, code)) {
+    const resolved = await ctx.resolve(specifier, importer, { skipSelf: true })
+    const file = resolved?.id
+    if (
+      !file ||
+      resolved.external ||
+      file.startsWith('\0') ||
+      file.includes('node_modules') ||
+      !EVALUATABLE_RE.test(file)
+    ) continue
+    imports[specifier] = file
+  }
+  return imports
+}
+
+// Build-time constants for css() values: Vite's `define` entries whose values
+// are JSON, `import.meta.env`, `process.env.NODE_ENV`, then explicit options.
+function buildDefines(config, explicit) {
+  const defines = {}
+  for (const [key, value] of Object.entries(config?.define ?? {})) {
+    try {
+      JSON.parse(value)
+      defines[key] = value
+    } catch { }
+  }
+  if (config?.env) defines['import.meta.env'] = JSON.stringify(config.env)
+  if (config) {
+    defines['process.env.NODE_ENV'] = JSON.stringify(config.isProduction ? 'production' : 'development')
+  }
+  return { ...defines, ...explicit }
+}
+
+// Breakpoint shorthand keys (`'@md'`, `'@between md lg'`) assume each
+// breakpoint is wider than the one before it. Numbers are px; rem/em are
+// converted at 16px so mixed units compare.
+function validateBreakpoints(breakpoints) {
+  if (breakpoints == null) return
+  let previous = null
+  for (const [name, value] of Object.entries(breakpoints)) {
+    const px = breakpointPx(value)
+    if (px === null) {
+      throw new Error(
+        `taikocss: theme.breakpoints.${name} must be a number of px or a px/rem/em length, got ${JSON.stringify(value)}.`
+      )
+    }
+    if (previous && px <= previous.px) {
+      throw new Error(
+        `taikocss: theme.breakpoints must increase: ${name} (${value}) is not wider than ${previous.name} (${previous.value}).`
+      )
+    }
+    previous = { name, value, px }
+  }
+}
+
+function breakpointPx(value) {
+  if (typeof value === 'number') return value
+  const match = /^(-?\d*\.?\d+)(px|rem|em)$/.exec(String(value).trim())
+  return match ? Number(match[1]) * (match[2] === 'px' ? 1 : 16) : null
+}
+
 function buildColorSchemeCSS(schemeName, variants) {
   const modules = []
   for (const [mode, tokens] of Object.entries(variants)) {
@@ -36,13 +158,75 @@ function buildColorSchemeCSS(schemeName, variants) {
 
 export function taiko(options = {}) {
   const theme = options.theme ?? null
+  validateBreakpoints(theme?.breakpoints)
   const themeJson = theme ? JSON.stringify(theme) : null
   const dir = options.css?.defaultDirection ?? 'ltr'
+  let defines = buildDefines(null, options.defines)
+  const helpers = options.helpers ?? null
+  const maxModules = options.maxModules ?? MAX_MODULES
+  // Imported modules by path, for collectModules
+  const moduleCache = new Map()
+  // Versions may be written as numbers; the transform takes strings
+  const targets = options.css?.targets
+    ? Object.fromEntries(Object.entries(options.css.targets).map(([browser, version]) => [browser, String(version)]))
+    : null
+
+  // Run the Rust transform and turn its result into the hook's return value
+  function emit(ctx, id, code, modules) {
+    let result
+    try {
+      result = transform(id, code, themeJson, dir, modules, defines, helpers, targets)
+    } catch (err) {
+      ctx.error(err.message)
+    }
+
+    // Re-transform this file when a module it was evaluated against changes
+    for (const dep of result.dependencies ?? []) ctx.addWatchFile?.(dep)
+
+    const hasWork =
+      result.cssRules.length > 0 ||
+      (result.globalCss?.length ?? 0) > 0 ||
+      (result.keyframes?.length ?? 0) > 0
+
+    if (!hasWork) return
+
+    let imports = ''
+
+    const processRule = (rule, prefix) => {
+      const vid = prefix ? `virtual:taikocss/${prefix}-${rule.hash}.css` : `virtual:taikocss/${rule.hash}.css`;
+      const existing = cssMap.get(vid);
+
+      let version = cssVersions.get(vid) || 0;
+
+      // If the Rust compiler gave us brand new or updated CSS
+      if (!existing || existing.css !== rule.css) {
+        version++; // Bump the cache-buster
+        cssVersions.set(vid, version);
+        cssMap.set(vid, { css: rule.css, map: rule.map ?? null });
+      }
+
+      // Append the ?v= version counter. This guarantees the browser fetches the new styles!
+      imports += `import "${vid}?v=${version}";\n`;
+    };
+
+    for (const rule of result.globalCss ?? []) processRule(rule, 'global');
+    for (const kf of result.keyframes ?? []) processRule(kf, 'kf');
+    for (const rule of result.cssRules) processRule(rule, '');
+
+    return {
+      code: imports + result.code,
+      map: result.map ?? null,
+    }
+  }
 
   return {
     name: 'taikocss',
     enforce: 'pre',
 
+    configResolved(config) {
+      defines = buildDefines(config, options.defines)
+    },
+
     buildStart() {
       if (!theme?.colorSchemes) return
       for (const [schemeName, variants] of Object.entries(theme.colorSchemes)) {
@@ -54,49 +238,23 @@ export function taiko(options = {}) {
 
     transform(code, id) {
       if (!/\.(t|j)sx?$/.test(id) || id.includes('node_modules')) return
-      if (!code.includes('css(') && !code.includes('globalCss`') && !code.includes('keyframes`')) return
+      if (
+        !code.includes('taikocss/css') &&
+        !/\b(?:css|globalCss|keyframes|cx|recipe)[(`]/.test(code)
+      ) return
 
-      let result
-      try {
-        result = transform(id, code, themeJson, dir)
-      } catch (err) {
-        this.error(err.message)
+      // Imported tokens and style objects need the import graph, which can
+      // only be resolved asynchronously; files without imports stay synchronous.
+      const canResolve = typeof this?.resolve === 'function'
+      if (canResolve && collectImports(id, code).length > 0) {
+        return collectModules(this, id, code, moduleCache, maxModules).then((modules) => emit(this, id, code, modules))
       }
+      return emit(this, id, code, null)
+    },
 
-      const hasWork =
-        result.cssRules.length > 0 ||
-        (result.globalCss?.length ?? 0) > 0 ||
-        (result.keyframes?.length ?? 0) > 0
-
-      if (!hasWork) return
-
-      let imports = ''
-
-      const processRule = (rule, prefix) => {
-        const vid = prefix ? `virtual:taikocss/${prefix}-${rule.hash}.css` : `virtual:taikocss/${rule.hash}.css`;
-        const existing = cssMap.get(vid);
-
-        let version = cssVersions.get(vid) || 0;
-
-        // If the Rust compiler gave us brand new or updated CSS
-        if (!existing || existing.css !== rule.css) {
-          version++; // Bump the cache-buster
-          cssVersions.set(vid, version);
-          cssMap.set(vid, { css: rule.css, map: rule.map ?? null });
-        }
-
-        // Append the ?v= version counter. This guarantees the browser fetches the new styles!
-        imports += `import "${vid}?v=${version}";\n`;
-      };
-
-      for (const rule of result.globalCss ?? []) processRule(rule, 'global');
-      for (const kf of result.keyframes ?? []) processRule(kf, 'kf');
-      for (const rule of result.cssRules) processRule(rule, '');
-
-      return {
-        code: imports + result.code,
-        map: result.map ?? null,
-      }
+    watchChange(id, change) {
+      // A created or deleted file can change how unchanged importers resolve
+      if (change?.event !== 'update') moduleCache.clear()
     },
 
     resolveId(id) {
@@ -1,5 +1,17 @@
-export declare function css(_styles: Record<string, unknown>): string;
-export declare function globalCss(_strings: TemplateStringsArray, ..._values: unknown[]): void;
-export declare function keyframes(_strings: TemplateStringsArray, ..._values: unknown[]): string;
+export declare function css(_styles: TemplateStringsArray | Record<string, unknown>, ..._values: unknown[]): string;
+export declare function globalCss(_styles: TemplateStringsArray | Record<string, unknown>, ..._values: unknown[]): void;
+export declare function keyframes(_frames: TemplateStringsArray | Record<string, unknown>, ..._values: unknown[]): string;
 export declare function container(..._args: unknown[]): Record<string, unknown>;
+export declare function cx(...args: Array<string | undefined | null | false | 0 | ''>): string;
+export declare function recipe(_config: unknown): (props?: Record<string, unknown>) => string;
+export declare function fallback(...values: Array<string | number>): string | number;
+export declare function px(value: number): string;
+export declare function rem(px: number): string;
+export declare function em(px: number, base?: number): string;
+export declare function fluid(min: number, max: number, minViewport?: number, maxViewport?: number): string;
+export declare function darken(color: string, amount: number): string;
+export declare function lighten(color: string, amount: number): string;
+export declare function alpha(color: string, opacity: number): string;
+export declare function mix(a: string, b: string, weight?: number): string;
+export declare function contrastText(background: string): string;
 //# sourceMappingURL=css.d.ts.map
\ No newline at end of file
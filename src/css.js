@@ -6,13 +6,65 @@
 // At build time the Vite plugin replaces every call with a static class-name
 // string and injects virtual CSS modules, so this code is never reached in a
 // production bundle.
-export function css(_styles) {
+export function css(_styles, ..._values) {
     return '';
 }
-export function globalCss(_strings, ..._values) { }
-export function keyframes(_strings, ..._values) {
+export function globalCss(_styles, ..._values) { }
+export function keyframes(_frames, ..._values) {
     return '';
 }
 export function container(..._args) {
     return {};
 }
+// Calls the plugin cannot collapse into one class join the class names.
+export function cx(...args) {
+    return args.filter(Boolean).join(' ');
+}
+export function recipe(_config) {
+    return () => '';
+}
+// Compile-time helpers. Inside css() values the plugin evaluates these at
+// build time; the implementations below mirror that output for tests.
+// The plugin emits one declaration per value; at runtime the last one wins.
+export function fallback(...values) {
+    return values[values.length - 1];
+}
+const ROOT_FONT_SIZE = 16;
+// Like the build: ten decimal places at most, without floating-point noise
+function formatNumber(n) {
+    return String(Number(n.toFixed(10)));
+}
+export function px(value) {
+    return `${formatNumber(value)}px`;
+}
+export function rem(px) {
+    return `${formatNumber(px / ROOT_FONT_SIZE)}rem`;
+}
+export function em(px, base = ROOT_FONT_SIZE) {
+    return `${formatNumber(px / base)}em`;
+}
+export function fluid(min, max, minViewport = 320, maxViewport = 1280) {
+    const slope = (max - min) / (maxViewport - minViewport);
+    const intercept = min - slope * minViewport;
+    return `clamp(${formatNumber(Math.min(min, max) / ROOT_FONT_SIZE)}rem, ` +
+        `${formatNumber(intercept / ROOT_FONT_SIZE)}rem + ${formatNumber(slope * 100)}vw, ` +
+        `${formatNumber(Math.max(min, max) / ROOT_FONT_SIZE)}rem)`;
+}
+// Colour functions. The build computes a concrete colour; at runtime the
+// equivalent CSS colour function is returned for the browser to resolve —
+// the output the build gives runtime colours when the targets support it.
+export function darken(color, amount) {
+    return `oklch(from ${color} calc(l - ${formatNumber(amount)}) c h)`;
+}
+export function lighten(color, amount) {
+    return `oklch(from ${color} calc(l + ${formatNumber(amount)}) c h)`;
+}
+export function alpha(color, opacity) {
+    return `color-mix(in srgb, ${color} ${formatNumber(opacity * 100)}%, transparent)`;
+}
+export function mix(a, b, weight = 0.5) {
+    return `color-mix(in oklab, ${a}, ${b} ${formatNumber(weight * 100)}%)`;
+}
+export function contrastText(background) {
+    return `contrast-color(${background})`;
+}
@@ -7,17 +7,20 @@
 // string and injects virtual CSS modules, so this code is never reached in a
 // production bundle.
 
-export function css(_styles: Record<string, unknown>): string {
+export function css(
+  _styles: TemplateStringsArray | Record<string, unknown>,
+  ..._values: unknown[]
+): string {
   return ''
 }
 
 export function globalCss(
-  _strings: TemplateStringsArray,
+  _styles: TemplateStringsArray | Record<string, unknown>,
   ..._values: unknown[]
 ): void {}
 
 export function keyframes(
-  _strings: TemplateStringsArray,
+  _frames: TemplateStringsArray | Record<string, unknown>,
   ..._values: unknown[]
 ): string {
   return ''
@@ -25,4 +28,72 @@ export function keyframes(
 
 export function container(..._args: unknown[]): Record<string, unknown> {
   return {}
-}
\ No newline at end of file
+}
+
+// Calls the plugin cannot collapse into one class join the class names.
+export function cx(...args: Array<string | undefined | null | false | 0 | ''>): string {
+  return args.filter(Boolean).join(' ')
+}
+
+export function recipe(_config: unknown): (props?: Record<string, unknown>) => string {
+  return () => ''
+}
+
+// Compile-time helpers. Inside css() values the plugin evaluates these at
+// build time; the implementations below mirror that output for tests.
+
+// The plugin emits one declaration per value; at runtime the last one wins.
+export function fallback(...values: Array<string | number>): string | number {
+  return values[values.length - 1]
+}
+
+const ROOT_FONT_SIZE = 16
+
+// Like the build: ten decimal places at most, without floating-point noise
+function formatNumber(n: number): string {
+  return String(Number(n.toFixed(10)))
+}
+
+export function px(value: number): string {
+  return `${formatNumber(value)}px`
+}
+
+export function rem(px: number): string {
+  return `${formatNumber(px / ROOT_FONT_SIZE)}rem`
+}
+
+export function em(px: number, base: number = ROOT_FONT_SIZE): string {
+  return `${formatNumber(px / base)}em`
+}
+
+export function fluid(min: number, max: number, minViewport = 320, maxViewport = 1280): string {
+  const slope = (max - min) / (maxViewport - minViewport)
+  const intercept = min - slope * minViewport
+  return `clamp(${formatNumber(Math.min(min, max) / ROOT_FONT_SIZE)}rem, ` +
+    `${formatNumber(intercept / ROOT_FONT_SIZE)}rem + ${formatNumber(slope * 100)}vw, ` +
+    `${formatNumber(Math.max(min, max) / ROOT_FONT_SIZE)}rem)`
+}
+
+// Colour functions. The build computes a concrete colour; at runtime the
+// equivalent CSS colour function is returned for the browser to resolve —
+// the output the build gives runtime colours when the targets support it.
+
+export function darken(color: string, amount: number): string {
+  return `oklch(from ${color} calc(l - ${formatNumber(amount)}) c h)`
+}
+
+export function lighten(color: string, amount: number): string {
+  return `oklch(from ${color} calc(l + ${formatNumber(amount)}) c h)`
+}
+
+export function alpha(color: string, opacity: number): string {
+  return `color-mix(in srgb, ${color} ${formatNumber(opacity * 100)}%, transparent)`
+}
+
+export function mix(a: string, b: string, weight = 0.5): string {
+  return `color-mix(in oklab, ${a}, ${b} ${formatNumber(weight * 100)}%)`
+}
+
+export function contrastText(background: string): string {
+  return `contrast-color(${background})`
+}
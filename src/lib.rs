@@ -4,16 +4,22 @@ use napi_derive::napi;
 use napi::bindgen_prelude::*;
 
 use std::path::Path;
+use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 use oxc_allocator::Allocator;
 use oxc_parser::{Parser, ParseOptions, ParserReturn};
-use oxc_span::{SourceType, GetSpan};
+use oxc_span::{SourceType, GetSpan, Span};
 use oxc_ast::ast::*;
+use oxc_ast_visit::{Visit, walk};
 use oxc_codegen::{Codegen, CodegenOptions};
+use oxc_semantic::{Scoping, Semantic, SemanticBuilder, SymbolId};
 
 use lightningcss::stylesheet::{StyleSheet, ParserOptions, PrinterOptions, MinifyOptions};
 use lightningcss::targets::{Targets, Browsers};
+use lightningcss::traits::{Parse, ToCss};
+use lightningcss::values::color::{CssColor, HueInterpolationMethod, OKLAB, OKLCH, SRGBLinear};
 
 use parcel_sourcemap::SourceMap;
 
@@ -47,14 +53,36 @@ pub struct KeyframeRule {
     pub map: Option<String>,
 }
 
+#[napi(object)]
+pub struct RecipeTypes {
+    /// The variable the recipe is assigned to, if any
+    pub name: Option<String>,
+    /// TypeScript declaration of the recipe's variant props, e.g.
+    /// `export type ButtonVariants = { size?: "sm" | "lg" }`
+    pub types: String,
+}
+
 #[napi(object)]
 pub struct TransformResult {
     pub code: String,
     pub css_rules: Vec<ExtractedCssRule>,
     pub global_css: Vec<GlobalCssRule>,
     pub keyframes: Vec<KeyframeRule>,
+    pub recipes: Vec<RecipeTypes>,
     /// V3 source map JSON for the transformed JS.
     pub map: Option<String>,
+    /// Ids of the modules whose values were folded into this file's CSS, so
+    /// the bundler can re-transform it when one of them changes.
+    pub dependencies: Vec<String>,
+}
+
+/// A dependency made available for cross-module evaluation.
+#[napi(object)]
+pub struct ModuleSource {
+    pub code: String,
+    /// Import specifier → resolved module id, for the specifiers of `code`
+    /// that were resolved
+    pub imports: HashMap<String, String>,
 }
 
 // ---------------------------------------------------------------------------
@@ -77,6 +105,72 @@ const UNITLESS: &[&str] = &[
     "counter-reset",
 ];
 
+/// Properties whose list values (`margin: [0, 8]` as a token) are separated
+/// by spaces; every other list is comma-separated (font stacks, shadow layers,
+/// transitions).
+const SPACE_SEPARATED: &[&str] = &[
+    "margin",
+    "margin-block",
+    "margin-inline",
+    "padding",
+    "padding-block",
+    "padding-inline",
+    "inset",
+    "inset-block",
+    "inset-inline",
+    "gap",
+    "border-radius",
+    "border-width",
+    "border-style",
+    "border-color",
+    "grid-area",
+    "grid-template-areas",
+    "grid-template-columns",
+    "grid-template-rows",
+    "transform",
+    "translate",
+    "scale",
+    "filter",
+    "backdrop-filter",
+    "place-items",
+    "place-content",
+    "place-self",
+];
+
+/// Properties whose values are comma-separated lists. An inline array on one
+/// of these could be per-breakpoint values or the list itself, so it must be
+/// spelled out as a string, a const list, the object form or `fallback()`.
+const COMMA_LISTS: &[&str] = &[
+    "font-family",
+    "transition",
+    "transition-property",
+    "transition-duration",
+    "transition-timing-function",
+    "animation",
+    "animation-name",
+    "box-shadow",
+    "text-shadow",
+    "background",
+    "background-image",
+    "background-position",
+    "background-size",
+    "mask",
+    "mask-image",
+    "will-change",
+    "font-feature-settings",
+    "font-variation-settings",
+];
+
+/// Generic font families and keywords, never quoted in a font stack.
+const FONT_KEYWORDS: &[&str] = &[
+    "serif", "sans-serif", "monospace", "cursive", "fantasy", "system-ui", "ui-serif",
+    "ui-sans-serif", "ui-monospace", "ui-rounded", "emoji", "math", "fangsong",
+    "-apple-system", "BlinkMacSystemFont", "inherit", "initial", "unset", "revert",
+];
+
+/// The module specifier that the extractable API is imported from.
+const API_MODULE: &str = "taikocss/css";
+
 // ---------------------------------------------------------------------------
 // camelCase → kebab-case
 // ---------------------------------------------------------------------------
@@ -133,41 +227,167 @@ fn byte_offset_to_line_col(source: &str, offset: u32) -> (u32, u32) {
 // ---------------------------------------------------------------------------
 // Theme evaluation helpers
 //
-// A "theme value" is either a resolved string or a number. When the user
-// writes `theme.colors.primary` the walker traverses the JSON tree to find
-// the leaf. Arithmetic on numbers (*, +, -, /) is evaluated statically.
+// A "theme value" is a resolved string or number, a list of them (font
+// stacks, shadow layers), or a boolean / null / undefined produced by a
+// static condition. When the user writes
+// `theme.colors.primary` the walker traverses the JSON tree to find the leaf.
+// Arithmetic (*, +, -, /) on numbers and dimensions, comparisons and logical
+// operators are evaluated statically.
 // ---------------------------------------------------------------------------
 
 /// A resolved compile-time value from a theme member or arithmetic expression.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 enum ThemeValue {
     Str(String),
     Num(f64),
+    Bool(bool),
+    Null,
+    Undefined,
+    /// An array token, joined per property when printed
+    List(Vec<ThemeValue>),
 }
 
 impl ThemeValue {
+    fn from_json(value: &serde_json::Value) -> Option<ThemeValue> {
+        match value {
+            serde_json::Value::String(s) => Some(ThemeValue::Str(s.clone())),
+            serde_json::Value::Number(n) => Some(ThemeValue::Num(n.as_f64().unwrap_or(0.0))),
+            serde_json::Value::Bool(b) => Some(ThemeValue::Bool(*b)),
+            serde_json::Value::Null => Some(ThemeValue::Null),
+            serde_json::Value::Array(items) => {
+                items.iter().map(ThemeValue::from_json).collect::<Option<_>>().map(ThemeValue::List)
+            }
+            serde_json::Value::Object(_) => None,
+        }
+    }
+
+    /// JS truthiness, for statically evaluated conditions.
+    fn is_truthy(&self) -> bool {
+        match self {
+            ThemeValue::Str(s) => !s.is_empty(),
+            ThemeValue::Num(n) => *n != 0.0 && !n.is_nan(),
+            ThemeValue::Bool(b) => *b,
+            ThemeValue::Null | ThemeValue::Undefined => false,
+            ThemeValue::List(_) => true,
+        }
+    }
+
+    fn is_nullish(&self) -> bool {
+        matches!(self, ThemeValue::Null | ThemeValue::Undefined)
+    }
+
+    /// `false`, `null` and `undefined` as a property value omit the declaration.
+    fn omits_declaration(&self) -> bool {
+        matches!(self, ThemeValue::Bool(false) | ThemeValue::Null | ThemeValue::Undefined)
+    }
+
+    /// JS `String(value)`, for template interpolation and concatenation.
+    fn to_js_string(&self) -> String {
+        match self {
+            ThemeValue::Str(s) => s.clone(),
+            ThemeValue::Num(n) => format_js_number(*n),
+            ThemeValue::Bool(b) => b.to_string(),
+            ThemeValue::Null => "null".to_string(),
+            ThemeValue::Undefined => "undefined".to_string(),
+            ThemeValue::List(items) => items
+                .iter()
+                .map(|item| if item.is_nullish() { String::new() } else { item.to_js_string() })
+                .collect::<Vec<_>>()
+                .join(","),
+        }
+    }
+
+    /// JS `Number(value)`, for relational comparisons.
+    fn to_number(&self) -> f64 {
+        match self {
+            ThemeValue::Str(s) if s.trim().is_empty() => 0.0,
+            ThemeValue::Str(s) => s.trim().parse().unwrap_or(f64::NAN),
+            ThemeValue::Num(n) => *n,
+            ThemeValue::Bool(b) => f64::from(u8::from(*b)),
+            ThemeValue::Null => 0.0,
+            ThemeValue::Undefined => f64::NAN,
+            ThemeValue::List(items) => match items.as_slice() {
+                [] => 0.0,
+                [item] => ThemeValue::Str(item.to_js_string()).to_number(),
+                _ => f64::NAN,
+            },
+        }
+    }
+
+    /// JS `==`.
+    fn loose_eq(&self, other: &ThemeValue) -> bool {
+        match (self, other) {
+            (a, b) if a.is_nullish() || b.is_nullish() => a.is_nullish() && b.is_nullish(),
+            (ThemeValue::Str(a), ThemeValue::Str(b)) => a == b,
+            (a, b) => a.to_number() == b.to_number(),
+        }
+    }
+
     fn to_css_value(&self, prop_name: &str) -> String {
         match self {
             ThemeValue::Str(s) => s.clone(),
-            ThemeValue::Num(n) => {
-                if UNITLESS.contains(&prop_name) {
-                    if n.fract() == 0.0 {
-                        format!("{}", *n as i64)
-                    } else {
-                        format!("{}", n)
-                    }
-                } else if *n == 0.0 {
-                    "0".to_string()
-                } else if n.fract() == 0.0 {
-                    format!("{}px", *n as i64)
-                } else {
-                    format!("{}px", n)
-                }
-            }
+            ThemeValue::Num(n) if UNITLESS.contains(&prop_name) => format_js_number(*n),
+            ThemeValue::Num(n) if *n == 0.0 => "0".to_string(),
+            ThemeValue::Num(n) => format!("{}px", format_js_number(*n)),
+            ThemeValue::List(items) => css_list(items, prop_name),
+            other => other.to_js_string(),
         }
     }
 }
 
+/// Join a list token for `prop_name`: font stacks and grid areas are quoted,
+/// box and transform properties are space-separated, everything else is
+/// comma-separated. A nested list (one shadow layer) is space-separated.
+fn css_list(items: &[ThemeValue], prop_name: &str) -> String {
+    let separator = if SPACE_SEPARATED.contains(&prop_name) { " " } else { ", " };
+    items
+        .iter()
+        .filter(|item| !item.omits_declaration())
+        .map(|item| match item {
+            ThemeValue::Str(s) if prop_name == "font-family" && needs_font_quotes(s) => css_string(s),
+            ThemeValue::Str(s) if prop_name == "grid-template-areas" && !s.starts_with('"') => css_string(s),
+            ThemeValue::List(layer) => layer
+                .iter()
+                .filter(|item| !item.omits_declaration())
+                .map(|item| item.to_css_value(prop_name))
+                .collect::<Vec<_>>()
+                .join(" "),
+            item => item.to_css_value(prop_name),
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Whether a font family name must be quoted: anything but a keyword or a
+/// single identifier.
+fn needs_font_quotes(family: &str) -> bool {
+    !(family.starts_with(['"', '\''])
+        || FONT_KEYWORDS.contains(&family)
+        || (!family.starts_with(|c: char| c.is_ascii_digit())
+            && family.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')))
+}
+
+fn css_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// JS `String(number)` without binary floating-point noise: `0.1 + 0.2`
+/// prints as `0.3`, not `0.30000000000000004`.
+fn format_js_number(n: f64) -> String {
+    if n.is_nan() {
+        return "NaN".to_string();
+    }
+    if n.is_infinite() {
+        return if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    // Ten decimal places is well past anything a browser renders
+    let fixed = format!("{:.10}", n);
+    match fixed.trim_end_matches('0').trim_end_matches('.') {
+        "-0" => "0".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
 /// Resolve a chain of member accesses on the theme JSON tree.
 /// E.g. `theme.colors.primary` → walks ["colors"]["primary"].
 fn resolve_theme_member(
@@ -194,28 +414,41 @@ fn resolve_theme_member(
             }
         }
     }
-    match cur {
-        serde_json::Value::String(s) => Ok(ThemeValue::Str(s.clone())),
-        serde_json::Value::Number(n) => Ok(ThemeValue::Num(n.as_f64().unwrap_or(0.0))),
-        _ => {
+    match ThemeValue::from_json(cur) {
+        Some(value) => Ok(value),
+        None => {
             let (line, col) = byte_offset_to_line_col(source, offset);
             Err(Error::new(
                 Status::InvalidArg,
                 format!(
-                    "{}:{}:{}: css() — theme.{} resolves to a non-scalar value (object/array). \
-                     Only string and number leaf values are supported.",
-                    filename, line, col, parts.join(".")
+                    "{}:{}:{}: css() — theme.{} is not a string, number or list, so it cannot be \
+                     used as a value.\n\
+                     Hint: spread a composite token (`...theme.{}`) to expand its declarations.",
+                    filename, line, col, parts.join("."), parts.join(".")
                 ),
             ))
         }
     }
 }
 
+/// Find the longest prefix of `chain` in the define map, returning its value
+/// and the remaining keys.
+fn lookup_define<'d, 'c>(
+    defines: &'d HashMap<String, serde_json::Value>,
+    chain: &'c [&'c str],
+) -> Option<(&'d serde_json::Value, &'c [&'c str])> {
+    (1..=chain.len())
+        .rev()
+        .find_map(|n| defines.get(&chain[..n].join(".")).map(|value| (value, &chain[n..])))
+}
+
 /// Collect the member chain from a MemberExpression: `theme.colors.primary`
 /// → `["theme", "colors", "primary"]`. Returns None if any access is computed.
+/// `import.meta` roots a chain as `["import", "meta", …]`.
 fn collect_member_chain<'a>(expr: &'a Expression) -> Option<Vec<&'a str>> {
-    match expr {
+    match expr.get_inner_expression() {
         Expression::Identifier(id) => Some(vec![id.name.as_str()]),
+        Expression::MetaProperty(meta) => Some(vec![meta.meta.name.as_str(), meta.property.name.as_str()]),
         Expression::StaticMemberExpression(mem) => {
             let mut chain = collect_member_chain(&mem.object)?;
             chain.push(mem.property.name.as_str());
@@ -227,308 +460,2389 @@ fn collect_member_chain<'a>(expr: &'a Expression) -> Option<Vec<&'a str>> {
     }
 }
 
-/// Evaluate an expression that may reference the theme object or be a plain
-/// literal. Returns ThemeValue if it can be statically resolved, or an error.
-fn eval_value_expr(
-    expr: &Expression,
-    theme: Option<&serde_json::Value>,
-    filename: &str,
-    source: &str,
-) -> Result<ThemeValue> {
-    match expr {
-        Expression::StringLiteral(s) => Ok(ThemeValue::Str(s.value.to_string())),
-        Expression::NumericLiteral(n) => Ok(ThemeValue::Num(n.value)),
+// ---------------------------------------------------------------------------
+// Dimension arithmetic
+//
+// Arithmetic operands are read as quantities: plain numbers, dimensions
+// parsed from token strings (`'1.5rem'`, `'4px'`, `'50%'`), or opaque CSS
+// terms (`'var(--gap)'`, `'calc(100% - 2rem)'`) that only the browser can
+// resolve. Like units fold, absolute units convert (`'1in' + '4px'`), and
+// anything else compatible becomes a `calc()` expression. Results go back into
+// a ThemeValue as the CSS text, so `theme.spacing.md * 2` yields `'2rem'`.
+// ---------------------------------------------------------------------------
 
-        // Binary expressions: arithmetic on theme values
-        Expression::BinaryExpression(bin) => {
-            let left = eval_value_expr(&bin.left, theme, filename, source)?;
-            let right = eval_value_expr(&bin.right, theme, filename, source)?;
-            match bin.operator {
-                BinaryOperator::Addition => match (&left, &right) {
-                    (ThemeValue::Num(a), ThemeValue::Num(b)) => Ok(ThemeValue::Num(a + b)),
-                    (ThemeValue::Str(a), ThemeValue::Str(b)) => {
-                        Ok(ThemeValue::Str(format!("{}{}", a, b)))
-                    }
-                    (ThemeValue::Str(a), ThemeValue::Num(b)) => {
-                        Ok(ThemeValue::Str(format!("{}{}", a, b)))
-                    }
-                    (ThemeValue::Num(a), ThemeValue::Str(b)) => {
-                        Ok(ThemeValue::Str(format!("{}{}", a, b)))
-                    }
-                },
-                BinaryOperator::Subtraction => match (&left, &right) {
-                    (ThemeValue::Num(a), ThemeValue::Num(b)) => Ok(ThemeValue::Num(a - b)),
-                    _ => {
-                        let (line, col) = byte_offset_to_line_col(source, bin.span.start);
-                        Err(Error::new(Status::InvalidArg, format!(
-                            "{}:{}:{}: css() — subtraction is only supported between numbers.",
-                            filename, line, col
-                        )))
-                    }
-                },
-                BinaryOperator::Multiplication => match (&left, &right) {
-                    (ThemeValue::Num(a), ThemeValue::Num(b)) => Ok(ThemeValue::Num(a * b)),
-                    _ => {
-                        let (line, col) = byte_offset_to_line_col(source, bin.span.start);
-                        Err(Error::new(Status::InvalidArg, format!(
-                            "{}:{}:{}: css() — multiplication is only supported between numbers.",
-                            filename, line, col
-                        )))
-                    }
-                },
-                BinaryOperator::Division => match (&left, &right) {
-                    (ThemeValue::Num(a), ThemeValue::Num(b)) if *b != 0.0 => {
-                        Ok(ThemeValue::Num(a / b))
-                    }
-                    _ => {
-                        let (line, col) = byte_offset_to_line_col(source, bin.span.start);
-                        Err(Error::new(Status::InvalidArg, format!(
-                            "{}:{}:{}: css() — division by zero or non-numeric operand.",
-                            filename, line, col
-                        )))
-                    }
-                },
-                _ => {
-                    let (line, col) = byte_offset_to_line_col(source, bin.span.start);
-                    Err(Error::new(Status::InvalidArg, format!(
-                        "{}:{}:{}: css() — unsupported binary operator in theme expression.",
-                        filename, line, col
-                    )))
-                }
-            }
-        }
+#[derive(Debug, Clone, PartialEq)]
+enum Quantity {
+    Num(f64),
+    Dim(f64, String),
+    /// A CSS term, parenthesised when compound, usable inside `calc()`
+    Calc(String),
+}
 
-        // Template literals: only static parts + theme member interpolations
-        Expression::TemplateLiteral(tpl) => {
-            let mut result = String::new();
-            for (i, quasi) in tpl.quasis.iter().enumerate() {
-                result.push_str(quasi.value.raw.as_str());
-                if i < tpl.expressions.len() {
-                    let val = eval_value_expr(&tpl.expressions[i], theme, filename, source)?;
-                    match val {
-                        ThemeValue::Str(s) => result.push_str(&s),
-                        ThemeValue::Num(n) => result.push_str(&format!("{}", n)),
-                    }
-                }
-            }
-            Ok(ThemeValue::Str(result))
-        }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnitKind {
+    Length,
+    Percent,
+    Angle,
+    Time,
+    Frequency,
+    Resolution,
+    Flex,
+}
 
-        // Computed member access (e.g. theme.colors[dynamicKey]) — explicit error
-        Expression::ComputedMemberExpression(cme) => {
-            let (line, col) = byte_offset_to_line_col(source, cme.span.start);
-            return Err(Error::new(Status::InvalidArg, format!(
-                "{}:{}:{}: css() — computed member access (e.g. theme.colors[key]) is not                  supported. Use a static property name.\n                 Hint: extract the value to a constant or use a CSS variable.",
-                filename, line, col
-            )));
+fn unit_kind(unit: &str) -> UnitKind {
+    match unit {
+        "%" => UnitKind::Percent,
+        "deg" | "grad" | "rad" | "turn" => UnitKind::Angle,
+        "s" | "ms" => UnitKind::Time,
+        "hz" | "khz" => UnitKind::Frequency,
+        "dpi" | "dpcm" | "dppx" | "x" => UnitKind::Resolution,
+        "fr" => UnitKind::Flex,
+        _ => UnitKind::Length,
+    }
+}
+
+/// Size of an absolute unit in its kind's canonical unit (px, deg, s, Hz, dppx).
+fn absolute_factor(unit: &str) -> Option<f64> {
+    Some(match unit {
+        "px" | "deg" | "s" | "hz" | "dppx" | "x" => 1.0,
+        "in" => 96.0,
+        "cm" => 96.0 / 2.54,
+        "mm" => 96.0 / 25.4,
+        "q" => 96.0 / 101.6,
+        "pt" => 96.0 / 72.0,
+        "pc" => 16.0,
+        "grad" => 0.9,
+        "rad" => 180.0 / std::f64::consts::PI,
+        "turn" => 360.0,
+        "ms" => 0.001,
+        "khz" => 1000.0,
+        "dpi" => 1.0 / 96.0,
+        "dpcm" => 2.54 / 96.0,
+        _ => return None,
+    })
+}
+
+impl Quantity {
+    /// Read an operand; `None` for values that are not numeric at all.
+    fn parse(value: &ThemeValue) -> Option<Quantity> {
+        let s = match value {
+            ThemeValue::Num(n) => return Some(Quantity::Num(*n)),
+            ThemeValue::Str(s) => s.trim(),
+            _ => return None,
+        };
+        if let Some(inner) = s.strip_prefix("calc(").and_then(|rest| rest.strip_suffix(')')) {
+            return Some(Quantity::Calc(format!("({})", inner.trim())));
+        }
+        if ["var(", "env(", "min(", "max(", "clamp("].iter().any(|f| s.starts_with(f)) && s.ends_with(')') {
+            return Some(Quantity::Calc(s.to_string()));
+        }
+        let split = s
+            .char_indices()
+            .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && (c == '-' || c == '+'))))
+            .map(|(i, _)| i)?;
+        let (number, unit) = s.split_at(split);
+        let is_unit = unit == "%" || unit.chars().all(|c| c.is_ascii_alphabetic());
+        match number.parse::<f64>() {
+            Ok(n) if is_unit => Some(Quantity::Dim(n, unit.to_ascii_lowercase())),
+            _ => None,
         }
+    }
 
-        // Member expression: resolve against theme
-        Expression::StaticMemberExpression(_) | Expression::Identifier(_) => {
-            let chain = collect_member_chain(expr).ok_or_else(|| {
-                let (line, col) = byte_offset_to_line_col(source, expr.span().start);
-                Error::new(Status::InvalidArg, format!(
-                    "{}:{}:{}: css() — computed member access (e.g. theme.colors[key]) is not \
-                     supported. Use a static property name.\n\
-                     Hint: extract the value to a constant or use a CSS variable.",
-                    filename, line, col
-                ))
-            })?;
+    fn is_num(&self) -> bool {
+        matches!(self, Quantity::Num(_))
+    }
 
-            // Check if the chain starts with "theme"
-            if chain.first() == Some(&"theme") {
-                let theme_obj = theme.ok_or_else(|| {
-                    let (line, col) = byte_offset_to_line_col(source, expr.span().start);
-                    Error::new(Status::InvalidArg, format!(
-                        "{}:{}:{}: css() — 'theme' is referenced but no theme was provided to \
-                         the plugin.\n\
-                         Hint: add a theme to taiko({{ theme: yourTheme }}) in vite.config.js.",
-                        filename, line, col
-                    ))
-                })?;
-                let parts = &chain[1..]; // skip "theme"
-                resolve_theme_member(theme_obj, parts, filename, expr.span().start, source)
-            } else {
-                // A plain identifier that's not "theme" — dynamic, not supported
-                let (line, col) = byte_offset_to_line_col(source, expr.span().start);
-                Err(Error::new(
-                    Status::InvalidArg,
-                    format!(
-                        "{}:{}:{}: css() — only static values are supported \
-                         (identifier '{}' is a runtime variable).\n\
-                         Hint: extract the value to a constant or use a CSS variable.",
-                        filename, line, col,
-                        chain.first().unwrap_or(&"?")
-                    ),
-                ))
-            }
+    /// CSS text of the quantity as a `calc()` term.
+    fn term(&self) -> String {
+        match self {
+            Quantity::Num(n) => format_js_number(*n),
+            Quantity::Dim(n, unit) => format!("{}{}", format_js_number(*n), unit),
+            Quantity::Calc(term) => term.clone(),
         }
+    }
 
-        other => {
-            let (line, col) = byte_offset_to_line_col(source, other.span().start);
-            Err(Error::new(
-                Status::InvalidArg,
-                format!(
-                    "{}:{}:{}: css() — only static values are supported \
-                     (property: dynamic expression).\n\
-                     Hint: extract the value to a constant or use a CSS variable.",
-                    filename, line, col
-                ),
-            ))
+    fn into_value(self) -> ThemeValue {
+        match self {
+            Quantity::Num(n) => ThemeValue::Num(n),
+            Quantity::Dim(..) => ThemeValue::Str(self.term()),
+            Quantity::Calc(term) => ThemeValue::Str(match term.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+                Some(inner) => format!("calc({})", inner),
+                None => format!("calc({})", term),
+            }),
+        }
+    }
+}
+
+/// Evaluate `+ - * /` on two operands. `+` falls back to JS string
+/// concatenation unless both sides are quantities and one carries a unit.
+fn eval_arithmetic(
+    op: BinaryOperator,
+    left: ThemeValue,
+    right: ThemeValue,
+    offset: u32,
+    ctx: &EvalCtx,
+) -> Result<ThemeValue> {
+    let operands = Quantity::parse(&left).zip(Quantity::parse(&right));
+    let dimensional = matches!(&operands, Some((a, b)) if !(a.is_num() && b.is_num()));
+    if op == BinaryOperator::Addition && !dimensional {
+        return match (&left, &right) {
+            (ThemeValue::Num(a), ThemeValue::Num(b)) => Ok(ThemeValue::Num(a + b)),
+            (ThemeValue::Str(_), _) | (_, ThemeValue::Str(_)) => Ok(ThemeValue::Str(
+                format!("{}{}", left.to_js_string(), right.to_js_string()),
+            )),
+            _ => Err(ctx.error_at(offset, "css() — addition is only supported \
+                 between strings and numbers.".to_string())),
+        };
+    }
+    let Some((a, b)) = operands else {
+        let what = match op {
+            BinaryOperator::Subtraction => "subtraction",
+            BinaryOperator::Multiplication => "multiplication",
+            BinaryOperator::Division => "division",
+            BinaryOperator::Remainder => "remainder",
+            _ => "exponentiation",
+        };
+        return Err(ctx.error_at(offset, format!(
+            "css() — {} is only supported between numbers and dimensions, got {} and {}.",
+            what, describe_value(&left), describe_value(&right)
+        )));
+    };
+    let symbol = match op {
+        BinaryOperator::Addition => "+",
+        BinaryOperator::Subtraction => "-",
+        BinaryOperator::Multiplication => "*",
+        BinaryOperator::Division => "/",
+        BinaryOperator::Remainder => "%",
+        _ => "**",
+    };
+    let calc = || Ok(Quantity::Calc(format!("({} {} {})", a.term(), symbol, b.term())));
+    let incompatible = |reason: &str| Err(ctx.error_at(offset, format!(
+        "css() — cannot evaluate `{} {} {}`: {}.",
+        a.term(), symbol, b.term(), reason
+    )));
+    let result = match op {
+        BinaryOperator::Addition | BinaryOperator::Subtraction => {
+            let sign = if op == BinaryOperator::Addition { 1.0 } else { -1.0 };
+            match (&a, &b) {
+                (Quantity::Num(x), Quantity::Num(y)) => Ok(Quantity::Num(x + sign * y)),
+                (Quantity::Dim(x, u), Quantity::Dim(y, v)) if u == v => {
+                    Ok(Quantity::Dim(x + sign * y, u.clone()))
+                }
+                (Quantity::Dim(x, u), Quantity::Dim(y, v)) => {
+                    let (ku, kv) = (unit_kind(u), unit_kind(v));
+                    match (absolute_factor(u), absolute_factor(v)) {
+                        (Some(fu), Some(fv)) if ku == kv => Ok(Quantity::Dim(x + sign * y * fv / fu, u.clone())),
+                        _ if ku == UnitKind::Flex || kv == UnitKind::Flex => {
+                            incompatible("fr cannot be used in calc()")
+                        }
+                        _ if ku == kv || ku == UnitKind::Percent || kv == UnitKind::Percent => calc(),
+                        _ => incompatible("incompatible units"),
+                    }
+                }
+                (Quantity::Num(_), Quantity::Dim(..)) | (Quantity::Dim(..), Quantity::Num(_)) => {
+                    incompatible("one side has a unit and the other does not.\n\
+                         Hint: give the number a unit, e.g. '4px'")
+                }
+                _ => calc(),
+            }
         }
+        BinaryOperator::Multiplication => match (&a, &b) {
+            (Quantity::Num(x), Quantity::Num(y)) => Ok(Quantity::Num(x * y)),
+            (Quantity::Dim(x, u), Quantity::Num(k)) | (Quantity::Num(k), Quantity::Dim(x, u)) => {
+                Ok(Quantity::Dim(x * k, u.clone()))
+            }
+            (Quantity::Dim(..), Quantity::Dim(..)) => {
+                incompatible("at least one side must be a unitless number")
+            }
+            _ => calc(),
+        },
+        BinaryOperator::Division => match (&a, &b) {
+            (_, Quantity::Num(y) | Quantity::Dim(y, _)) if *y == 0.0 => {
+                return Err(ctx.error_at(offset, "css() — division by zero.".to_string()));
+            }
+            (Quantity::Num(x), Quantity::Num(y)) => Ok(Quantity::Num(x / y)),
+            (Quantity::Dim(x, u), Quantity::Num(k)) => Ok(Quantity::Dim(x / k, u.clone())),
+            (Quantity::Dim(x, u), Quantity::Dim(y, v)) => match (absolute_factor(u), absolute_factor(v)) {
+                _ if u == v => Ok(Quantity::Num(x / y)),
+                (Some(fu), Some(fv)) if unit_kind(u) == unit_kind(v) => Ok(Quantity::Num(x * fu / (y * fv))),
+                _ => incompatible("the units do not convert into each other"),
+            },
+            (Quantity::Num(_), Quantity::Dim(..)) => {
+                incompatible("a number cannot be divided by a dimension")
+            }
+            _ => calc(),
+        },
+        // calc() has no remainder or power operator, so both stay static-only
+        BinaryOperator::Remainder => match (&a, &b) {
+            (_, Quantity::Num(y) | Quantity::Dim(y, _)) if *y == 0.0 => {
+                return Err(ctx.error_at(offset, "css() — remainder of a division by zero.".to_string()));
+            }
+            (Quantity::Num(x), Quantity::Num(y)) => Ok(Quantity::Num(x % y)),
+            (Quantity::Dim(x, u), Quantity::Dim(y, v)) if u == v => Ok(Quantity::Dim(x % y, u.clone())),
+            _ => incompatible("% needs two numbers, or two dimensions in the same unit"),
+        },
+        _ => match (&a, &b) {
+            (Quantity::Num(x), Quantity::Num(y)) => Ok(Quantity::Num(x.powf(*y))),
+            _ => incompatible("** only applies to unitless numbers"),
+        },
+    };
+    match result? {
+        Quantity::Num(n) | Quantity::Dim(n, _) if !n.is_finite() => Err(ctx.error_at(offset, format!(
+            "css() — `{} {} {}` does not evaluate to a finite number.",
+            a.term(), symbol, b.term()
+        ))),
+        quantity => Ok(quantity.into_value()),
     }
 }
 
 // ---------------------------------------------------------------------------
-// ObjectExpression → raw CSS string (recursive)
+// Module scopes
 //
-// `theme` is optional — when present, member expressions starting with
-// "theme." are resolved against it. When absent, any non-literal value
-// remains an error (same as before).
+// Every JS/TS module the evaluator can see — the file being transformed plus
+// whatever the bundler pre-resolved into the module map — gets a ModuleScope
+// recording its top-level `const` bindings, its imports and its exports.
+// Identifiers and member paths (`SIZES.md`, `Colors['primary']`,
+// `tokens.radii.md`) resolve through these down to the literal they name,
+// following imports across modules. `let`/`var` bindings and reassigned
+// consts are reported instead of silently folded.
 // ---------------------------------------------------------------------------
 
-fn object_to_css(
-    obj: &ObjectExpression,
-    indent: usize,
-    filename: &str,
-    source: &str,
-    theme: Option<&serde_json::Value>,
-    // resolved keyframe names in scope: identifier name → "kf_<hash>"
-    keyframe_names: &HashMap<String, String>,
-) -> Result<String> {
-    let pad = "  ".repeat(indent);
-    let mut css = String::new();
+/// What an exported name refers to inside its module.
+enum ExportRef<'a> {
+    /// `export const x = …` or `export { x as y }`
+    Local(SymbolId),
+    /// `export default <expr>`
+    Default(&'a Expression<'a>),
+    /// `export { x as y } from './z'` — (specifier, imported name)
+    Reexport(&'a str, &'a str),
+}
 
-    for prop in &obj.properties {
-        match prop {
-            ObjectPropertyKind::ObjectProperty(p) => {
-                let key_str: String = match &p.key {
-                    PropertyKey::StringLiteral(s) => s.value.to_string(),
-                    PropertyKey::StaticIdentifier(id) => id.name.to_string(),
-                    other => {
-                        let (line, col) = byte_offset_to_line_col(source, other.span().start);
-                        return Err(Error::new(
-                            Status::InvalidArg,
-                            format!(
-                                "{}:{}:{}: css() — computed/private property keys are not \
-                                 supported.\n\
-                                 Hint: use a plain string or identifier as the property name.",
-                                filename, line, col
-                            ),
-                        ));
-                    }
-                };
+struct ModuleScope<'s, 'a> {
+    /// Position in the module graph, used to key the evaluation stack
+    index: usize,
+    filename: &'s str,
+    source: &'s str,
+    scoping: &'s Scoping,
+    api: ApiBindings<'s>,
+    /// Import specifier → resolved module id, as supplied by the bundler
+    resolved: &'s HashMap<String, String>,
+    /// Top-level binding → (declaration kind, initializer)
+    bindings: HashMap<SymbolId, (VariableDeclarationKind, Option<&'a Expression<'a>>)>,
+    /// Import binding → (specifier, imported name; `None` for `* as ns`)
+    imports: HashMap<SymbolId, (&'a str, Option<&'a str>)>,
+    exports: HashMap<&'a str, ExportRef<'a>>,
+    /// Specifiers of `export * from '…'`
+    star_exports: Vec<&'a str>,
+}
 
-                match &p.value {
-                    Expression::ObjectExpression(nested_obj) => {
-                        let nested_css = object_to_css(nested_obj, indent + 1, filename, source, theme, keyframe_names)?;
-                        css.push_str(&format!(
-                            "{}{} {{\n{}{}}}\n",
-                            pad, key_str, nested_css, pad
-                        ));
-                    }
-                    Expression::StringLiteral(s) => {
-                        let prop_name = camel_to_kebab(&key_str);
-                        css.push_str(&format!("{}{}: {};\n", pad, prop_name, s.value));
+impl<'s, 'a> ModuleScope<'s, 'a> {
+    fn collect(
+        index: usize,
+        filename: &'s str,
+        source: &'s str,
+        program: &'a Program<'a>,
+        scoping: &'s Scoping,
+        resolved: &'s HashMap<String, String>,
+    ) -> Self {
+        let mut bindings = HashMap::new();
+        let mut imports = HashMap::new();
+        let mut exports = HashMap::new();
+        let mut star_exports = vec![];
+
+        let mut add_declarations = |vd: &'a VariableDeclaration<'a>, exported: bool| {
+            for decl in &vd.declarations {
+                if let Some(id) = decl.id.get_binding_identifier() {
+                    bindings.insert(id.symbol_id(), (vd.kind, decl.init.as_ref()));
+                    if exported {
+                        exports.insert(id.name.as_str(), ExportRef::Local(id.symbol_id()));
                     }
-                    Expression::NumericLiteral(n) => {
-                        let prop_name = camel_to_kebab(&key_str);
-                        if UNITLESS.contains(&prop_name.as_str()) {
-                            css.push_str(&format!("{}{}: {};\n", pad, prop_name, n.value));
-                        } else if n.value.fract() == 0.0 {
-                            css.push_str(&format!(
-                                "{}{}: {}px;\n",
-                                pad, prop_name, n.value as i64
-                            ));
-                        } else {
-                            css.push_str(&format!("{}{}: {}px;\n", pad, prop_name, n.value));
-                        }
+                }
+            }
+        };
+
+        for stmt in &program.body {
+            match stmt {
+                Statement::VariableDeclaration(vd) => add_declarations(vd, false),
+                Statement::ExportNamedDeclaration(en) => {
+                    if let Some(Declaration::VariableDeclaration(vd)) = &en.declaration {
+                        add_declarations(vd, true);
                     }
-                    // Template literal: resolve keyframe references + static concatenation
-                    Expression::TemplateLiteral(tpl) => {
-                        let prop_name = camel_to_kebab(&key_str);
-                        let mut val = String::new();
-                        for (i, quasi) in tpl.quasis.iter().enumerate() {
-                            val.push_str(quasi.value.raw.as_str());
-                            if i < tpl.expressions.len() {
-                                let interp = &tpl.expressions[i];
-                                // Check if the interpolation is a known keyframe binding
-                                if let Expression::Identifier(id) = interp {
-                                    if let Some(kf_name) = keyframe_names.get(id.name.as_str()) {
-                                        val.push_str(kf_name);
-                                        continue;
-                                    }
-                                }
-                                // Otherwise try to evaluate as a theme value
-                                match eval_value_expr(interp, theme, filename, source)? {
-                                    ThemeValue::Str(s) => val.push_str(&s),
-                                    ThemeValue::Num(n) => val.push_str(&format!("{}", n)),
-                                }
+                }
+                _ => {}
+            }
+        }
+
+        for stmt in &program.body {
+            match stmt {
+                Statement::ImportDeclaration(import) if !import.import_kind.is_type() => {
+                    let specifier = import.source.value.as_str();
+                    for spec in import.specifiers.iter().flatten() {
+                        let (local, imported) = match spec {
+                            ImportDeclarationSpecifier::ImportSpecifier(s) => {
+                                (&s.local, Some(s.imported.name().as_str()))
                             }
-                        }
-                        css.push_str(&format!("{}{}: {};\n", pad, prop_name, val));
+                            ImportDeclarationSpecifier::ImportDefaultSpecifier(s) => {
+                                (&s.local, Some("default"))
+                            }
+                            ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => {
+                                (&s.local, None)
+                            }
+                        };
+                        imports.insert(local.symbol_id(), (specifier, imported));
                     }
-                    other => {
-                        // Always try static evaluation — handles theme members, arithmetic,
-                        // template literals, and gives a "theme" error when theme is absent.
-                        match eval_value_expr(other, theme, filename, source) {
-                            Ok(tv) => {
-                                let prop_name = camel_to_kebab(&key_str);
-                                let val = tv.to_css_value(&prop_name);
-                                css.push_str(&format!("{}{}: {};\n", pad, prop_name, val));
+                }
+                Statement::ExportNamedDeclaration(en) if en.declaration.is_none() => {
+                    for spec in &en.specifiers {
+                        let exported = spec.exported.name().as_str();
+                        match (&en.source, &spec.local) {
+                            (Some(src), local) => {
+                                exports.insert(
+                                    exported,
+                                    ExportRef::Reexport(src.value.as_str(), local.name().as_str()),
+                                );
+                            }
+                            (None, ModuleExportName::IdentifierReference(local)) => {
+                                let reference = scoping.get_reference(local.reference_id());
+                                if let Some(symbol) = reference.symbol_id() {
+                                    exports.insert(exported, ExportRef::Local(symbol));
+                                }
                             }
-                            Err(e) => return Err(e),
+                            _ => {}
                         }
                     }
                 }
-            }
-
-            ObjectPropertyKind::SpreadProperty(spread) => {
-                // Special case: container() spread is allowed
-                if let Expression::CallExpression(call) = &spread.argument {
-                    if is_container_call(call) {
-                        let expanded = expand_container_call(call, filename, source)?;
-                        css.push_str(&format!("{}{};\n", pad, expanded));
-                        continue;
+                Statement::ExportDefaultDeclaration(ed) => {
+                    if let Some(expr) = ed.declaration.as_expression() {
+                        exports.insert("default", ExportRef::Default(expr));
                     }
                 }
-                let (line, col) = byte_offset_to_line_col(source, spread.span.start);
-                return Err(Error::new(
-                    Status::InvalidArg,
-                    format!(
-                        "{}:{}:{}: css() — spread properties are not supported.\n\
-                         Hint: inline the spread object's properties directly into this css() call.",
-                        filename, line, col
-                    ),
-                ));
+                Statement::ExportAllDeclaration(ea) if ea.exported.is_none() => {
+                    star_exports.push(ea.source.value.as_str());
+                }
+                _ => {}
             }
         }
-    }
 
-    Ok(css)
+        ModuleScope {
+            index,
+            filename,
+            source,
+            scoping,
+            api: ApiBindings::collect(program, scoping),
+            resolved,
+            bindings,
+            imports,
+            exports,
+            star_exports,
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
-// container() call helpers
+// Module graph
+//
+// The bundler resolves imports asynchronously, so the Vite plugin walks the
+// import graph up front (see `collect_imports`) and hands `transform` a map of
+// module id → source + import table. JS/TS entries are parsed into the same
+// arena as the file being transformed; `.json` entries are parsed as JSON.
 // ---------------------------------------------------------------------------
 
-fn is_container_call(call: &CallExpression) -> bool {
-    matches!(
-        &call.callee,
-        Expression::Identifier(id) if id.name.as_str() == "container"
-    )
+enum Module<'s, 'a> {
+    Js(Box<ModuleScope<'s, 'a>>),
+    Json(serde_json::Value),
 }
 
-/// Expand `container('sidebar', 'inline-size')` or `container('inline-size')`
-/// into the CSS property declarations string (without the selector wrapper).
-fn expand_container_call(
-    call: &CallExpression,
-    filename: &str,
-    source: &str,
-) -> Result<String> {
-    let args: Vec<_> = call.arguments.iter()
-        .filter_map(|a| a.as_expression())
-        .collect();
+struct ModuleGraph<'s, 'a> {
+    modules: HashMap<&'s str, Module<'s, 'a>>,
+    /// Bindings being evaluated as (module index, symbol, name), for cycle detection
+    stack: RefCell<Vec<(usize, SymbolId, String)>>,
+    /// Module ids consulted during evaluation, reported for cache invalidation
+    dependencies: RefCell<Vec<String>>,
+    /// Keyframe and class maps used when evaluating inside a module other
+    /// than the one being transformed (symbols do not cross files)
+    no_names: HashMap<SymbolId, String>,
+}
+
+impl ModuleGraph<'_, '_> {
+    fn add_dependency(&self, id: &str) {
+        let mut deps = self.dependencies.borrow_mut();
+        if !deps.iter().any(|d| d == id) {
+            deps.push(id.to_string());
+        }
+    }
+}
+
+fn is_json_module(id: &str) -> bool {
+    id.split('?').next().is_some_and(|path| path.ends_with(".json"))
+}
+
+/// Parse a pre-resolved dependency. Modules that fail to parse are left out
+/// of the graph, so importing from them reports an unresolved binding.
+fn parse_module<'a>(allocator: &'a Allocator, id: &str, code: &'a str) -> Option<Program<'a>> {
+    let source_type = SourceType::from_path(id.split('?').next().unwrap_or(id)).unwrap_or_default();
+    let ParserReturn { program, errors, panicked, .. } =
+        Parser::new(allocator, code, source_type).parse();
+    (!panicked && errors.is_empty()).then_some(program)
+}
+
+/// What an identifier or member path statically refers to.
+#[derive(Clone, Copy)]
+enum Resolved<'g, 'a> {
+    /// An expression, evaluated in the scope of the module it appears in.
+    /// The symbol is the binding it was reached through, for cycle detection.
+    Expr(&'g ModuleScope<'g, 'a>, Option<SymbolId>, &'a Expression<'a>),
+    /// A value inside a JSON module
+    Json(&'g serde_json::Value),
+    /// `import * as ns` of a JS/TS module
+    Namespace(&'g ModuleScope<'g, 'a>),
+}
+
+// ---------------------------------------------------------------------------
+// Evaluation context — the inputs shared by the static evaluator and the
+// object serializer, bundled so they are not threaded one by one.
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy)]
+struct EvalCtx<'b, 'a> {
+    filename: &'b str,
+    source: &'b str,
+    theme: Option<&'b serde_json::Value>,
+    // keyframes extracted earlier in this file: binding → "kf_<hash>"
+    keyframe_names: &'b HashMap<SymbolId, String>,
+    // css() classes extracted earlier in this file: binding → "cls_<hash>"
+    class_names: &'b HashMap<SymbolId, String>,
+    /// Build-time constants: member chain (`process.env.NODE_ENV`) → value
+    defines: &'b HashMap<String, serde_json::Value>,
+    /// Project helpers from config: name → template with `{0}`, `{1}`, … slots
+    helpers: &'b HashMap<String, String>,
+    /// Condition keys (`_hover`, `_dark`) → selector or at-rule, built-ins
+    /// merged with `theme.conditions`
+    conditions: &'b HashMap<String, String>,
+    /// The module whose expressions are being evaluated
+    module: &'b ModuleScope<'b, 'a>,
+    graph: &'b ModuleGraph<'b, 'a>,
+    /// Browsers the generated CSS must work in
+    targets: Browsers,
+    /// Parameter and body bindings of the theme callback being evaluated
+    locals: Option<&'b CallbackScope<'a>>,
+}
+
+impl<'b, 'a> EvalCtx<'b, 'a> {
+    /// Build an `InvalidArg` error prefixed with `file:line:col`.
+    fn error_at(&self, offset: u32, message: String) -> Error {
+        let (line, col) = byte_offset_to_line_col(self.source, offset);
+        Error::new(Status::InvalidArg, format!("{}:{}:{}: {}", self.filename, line, col, message))
+    }
+
+    fn source_text(&self, span: Span) -> &str {
+        &self.source[span.start as usize..span.end as usize]
+    }
+
+    /// The binding an identifier refers to in this module; `None` for globals.
+    fn symbol_of(&self, id: &IdentifierReference) -> Option<SymbolId> {
+        self.module.scoping.get_reference(id.reference_id()).symbol_id()
+    }
+
+    /// The animation name of a keyframes binding extracted earlier in this
+    /// file, if `id` refers to one — not a parameter or local shadowing it.
+    fn keyframe_name(&self, id: &IdentifierReference) -> Option<&'b String> {
+        self.symbol_of(id).and_then(|symbol| self.keyframe_names.get(&symbol))
+    }
+
+    /// The same context, evaluating inside `module` instead.
+    fn for_module(&self, module: &'b ModuleScope<'b, 'a>) -> EvalCtx<'b, 'a> {
+        if module.index == self.module.index {
+            return *self;
+        }
+        EvalCtx {
+            filename: module.filename,
+            source: module.source,
+            theme: self.theme,
+            keyframe_names: &self.graph.no_names,
+            class_names: &self.graph.no_names,
+            defines: self.defines,
+            helpers: self.helpers,
+            conditions: self.conditions,
+            module,
+            graph: self.graph,
+            targets: self.targets,
+            locals: None,
+        }
+    }
+
+    /// Follow an identifier or member path to what it statically names.
+    /// Returns `Ok(None)` when the root is not a top-level binding or import
+    /// (theme parameters, runtime locals, globals).
+    fn resolve_path(&self, expr: &Expression) -> Result<Option<Resolved<'b, 'a>>> {
+        match expr.get_inner_expression() {
+            Expression::Identifier(id) => {
+                let Some(symbol) = self.module.scoping.get_reference(id.reference_id()).symbol_id() else {
+                    return Ok(None);
+                };
+                match self.locals.and_then(|locals| locals.get(&symbol)) {
+                    Some(local) => self.resolve_local(local, symbol, id).map(Some),
+                    None => self.resolve_symbol(self.module, symbol, id.span.start),
+                }
+            }
+            Expression::StaticMemberExpression(mem) => {
+                let Some(object) = self.resolve_object(&mem.object)? else {
+                    return Ok(None);
+                };
+                self.member(object, mem.property.name.as_str(), &mem.object).map(Some)
+            }
+            Expression::ComputedMemberExpression(mem) => {
+                let Some(object) = self.resolve_object(&mem.object)? else {
+                    return Ok(None);
+                };
+                let key = eval_value_expr(&mem.expression, self).map_err(|_| {
+                    self.error_at(mem.expression.span().start, format!(
+                        "css() — computed member access needs a key known at build time, \
+                         but '{}' is not static.\n\
+                         Hint: use a static property name or extract the key to a constant.",
+                        self.source_text(mem.expression.span())
+                    ))
+                })?;
+                self.member(object, &key.to_js_string(), &mem.object).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Resolve a name bound by the theme callback being evaluated.
+    fn resolve_local(
+        &self,
+        local: &'b LocalBinding<'a>,
+        symbol: SymbolId,
+        id: &IdentifierReference,
+    ) -> Result<Resolved<'b, 'a>> {
+        let path = match local {
+            LocalBinding::Const(init) => return Ok(Resolved::Expr(self.module, Some(symbol), init)),
+            LocalBinding::Theme(path) => path,
+        };
+        let theme = self.theme.ok_or_else(|| {
+            self.error_at(id.span.start, format!(
+                "css() — '{}' is the theme, but no theme was provided to the plugin.\n\
+                 Hint: add a theme to taiko({{ theme: yourTheme }}) in vite.config.js.",
+                id.name
+            ))
+        })?;
+        path.iter()
+            .try_fold(theme, |value, key| value.get(key))
+            .map(Resolved::Json)
+            .ok_or_else(|| {
+                self.error_at(id.span.start, format!(
+                    "css() — theme.{} does not exist in the theme object.\n\
+                     Hint: check your theme definition in vite.config.js.",
+                    path.join(".")
+                ))
+            })
+    }
+
+    /// Resolve a top-level binding or import of `module`. `offset` locates
+    /// errors in the current file.
+    fn resolve_symbol(
+        &self,
+        module: &'b ModuleScope<'b, 'a>,
+        symbol: SymbolId,
+        offset: u32,
+    ) -> Result<Option<Resolved<'b, 'a>>> {
+        let name = module.scoping.symbol_name(symbol);
+        if let Some((kind, init)) = module.bindings.get(&symbol) {
+            // Bindings of other modules are reported at their declaration
+            let (at, offset) = if module.index == self.module.index {
+                (*self, offset)
+            } else {
+                (self.for_module(module), module.scoping.symbol_span(symbol).start)
+            };
+            if module.scoping.symbol_is_mutated(symbol) {
+                return Err(at.error_at(offset, format!(
+                    "css() — '{}' is reassigned, so its value is not known at build time.",
+                    name
+                )));
+            }
+            if !kind.is_const() {
+                return Err(at.error_at(offset, format!(
+                    "css() — '{}' is declared with `{}`; only `const` bindings can be \
+                     evaluated at build time.\n\
+                     Hint: declare it with `const`.",
+                    name, kind.as_str()
+                )));
+            }
+            return Ok(init.map(|init| Resolved::Expr(module, Some(symbol), init.get_inner_expression())));
+        }
+        if let Some((specifier, imported)) = module.imports.get(&symbol) {
+            return self.resolve_import(module, specifier, *imported, name, offset, 0).map(Some);
+        }
+        Ok(None)
+    }
+
+    fn resolve_import(
+        &self,
+        module: &'b ModuleScope<'b, 'a>,
+        specifier: &str,
+        imported: Option<&str>,
+        local: &str,
+        offset: u32,
+        depth: usize,
+    ) -> Result<Resolved<'b, 'a>> {
+        let target = module
+            .resolved
+            .get(specifier)
+            .and_then(|id| self.graph.modules.get_key_value(id.as_str()));
+        let Some((id, target)) = target else {
+            return Err(self.error_at(offset, format!(
+                "css() — '{}' is imported from '{}', which could not be resolved at build time.\n\
+                 Hint: only local modules and JSON files can be evaluated; use a CSS variable \
+                 for anything else.",
+                local, specifier
+            )));
+        };
+        self.graph.add_dependency(id);
+
+        match (target, imported) {
+            (Module::Json(value), None | Some("default")) => Ok(Resolved::Json(value)),
+            (Module::Json(value), Some(name)) => value.get(name).map(Resolved::Json).ok_or_else(|| {
+                self.error_at(offset, format!("css() — '{}' does not export '{}'.", id, name))
+            }),
+            (Module::Js(scope), None) => Ok(Resolved::Namespace(scope)),
+            (Module::Js(scope), Some(name)) => self.resolve_export(scope, name, offset, depth),
+        }
+    }
+
+    fn resolve_export(
+        &self,
+        scope: &'b ModuleScope<'b, 'a>,
+        name: &str,
+        offset: u32,
+        depth: usize,
+    ) -> Result<Resolved<'b, 'a>> {
+        if depth > 32 {
+            return Err(self.error_at(offset, format!(
+                "css() — re-export chain for '{}' is too deep (circular `export … from`?).",
+                name
+            )));
+        }
+        match scope.exports.get(name) {
+            Some(ExportRef::Local(symbol)) => {
+                self.resolve_symbol(scope, *symbol, offset)?.ok_or_else(|| {
+                    self.error_at(offset, format!(
+                        "css() — '{}' exported by '{}' is not a static value.",
+                        name, scope.filename
+                    ))
+                })
+            }
+            Some(ExportRef::Default(expr)) => Ok(Resolved::Expr(scope, None, expr.get_inner_expression())),
+            Some(ExportRef::Reexport(specifier, imported)) => {
+                self.resolve_import(scope, specifier, Some(imported), name, offset, depth + 1)
+            }
+            None => {
+                for specifier in &scope.star_exports {
+                    let exports_name = scope
+                        .resolved
+                        .get(*specifier)
+                        .and_then(|id| self.graph.modules.get(id.as_str()))
+                        .is_some_and(|m| self.has_export(m, name, depth + 1));
+                    if exports_name {
+                        return self.resolve_import(scope, specifier, Some(name), name, offset, depth + 1);
+                    }
+                }
+                Err(self.error_at(offset, format!(
+                    "css() — '{}' does not export '{}'.",
+                    scope.filename, name
+                )))
+            }
+        }
+    }
+
+    fn has_export(&self, module: &Module, name: &str, depth: usize) -> bool {
+        match module {
+            Module::Json(value) => value.get(name).is_some(),
+            Module::Js(scope) => self.scope_has_export(scope, name, depth),
+        }
+    }
+
+    fn scope_has_export(&self, scope: &ModuleScope, name: &str, depth: usize) -> bool {
+        scope.exports.contains_key(name)
+            || (depth <= 32
+                && scope.star_exports.iter().any(|specifier| {
+                    scope
+                        .resolved
+                        .get(*specifier)
+                        .and_then(|id| self.graph.modules.get(id.as_str()))
+                        .is_some_and(|m| self.has_export(m, name, depth + 1))
+                }))
+    }
+
+    /// Resolve the object side of a member access, following const aliases
+    /// (`const B = A`) until a literal, JSON value or namespace is reached.
+    fn resolve_object(&self, expr: &Expression) -> Result<Option<Resolved<'b, 'a>>> {
+        let Some(mut resolved) = self.resolve_path(expr)? else {
+            return Ok(None);
+        };
+        while let Resolved::Expr(module, symbol, alias @ (Expression::Identifier(_)
+            | Expression::StaticMemberExpression(_)
+            | Expression::ComputedMemberExpression(_))) = resolved
+        {
+            let inner = self.for_module(module);
+            resolved = self.with_symbol(module, symbol, expr.span().start, || inner.resolve_path(alias))?
+                .ok_or_else(|| {
+                    self.error_at(expr.span().start, format!(
+                        "css() — '{}' is not a static object or array.",
+                        self.source_text(expr.span())
+                    ))
+                })?;
+        }
+        Ok(Some(resolved))
+    }
+
+    fn member(
+        &self,
+        object: Resolved<'b, 'a>,
+        key: &str,
+        object_expr: &Expression,
+    ) -> Result<Resolved<'b, 'a>> {
+        self.find_member(object, key, object_expr.span().start)?.ok_or_else(|| {
+            self.error_at(object_expr.span().start, format!(
+                "css() — '{}' has no static property '{}'.",
+                self.source_text(object_expr.span()), key
+            ))
+        })
+    }
+
+    /// The property `key` of a resolved object, or `None` when it has none.
+    /// Spreads are followed, so `{ ...A, y: 1 }.x` is `A.x`.
+    fn find_member(&self, object: Resolved<'b, 'a>, key: &str, offset: u32) -> Result<Option<Resolved<'b, 'a>>> {
+        let found = match object {
+            Resolved::Expr(module, symbol, Expression::ObjectExpression(obj)) => {
+                // Later properties win, matching JS semantics for duplicate keys
+                let inner = self.for_module(module);
+                return self.with_symbol(module, symbol, offset, || {
+                    for prop in obj.properties.iter().rev() {
+                        match prop {
+                            ObjectPropertyKind::ObjectProperty(p) if p.key.static_name().as_deref() == Some(key) => {
+                                return Ok(Some(Resolved::Expr(module, symbol, p.value.get_inner_expression())));
+                            }
+                            ObjectPropertyKind::ObjectProperty(_) => {}
+                            ObjectPropertyKind::SpreadProperty(spread) => {
+                                if let Some(value) = inner.spread_member(&spread.argument, key, offset)? {
+                                    return Ok(Some(value));
+                                }
+                            }
+                        }
+                    }
+                    Ok(None)
+                });
+            }
+            Resolved::Expr(module, symbol, Expression::ArrayExpression(arr)) => key
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| arr.elements.get(i))
+                .and_then(|el| el.as_expression())
+                .map(|v| Resolved::Expr(module, symbol, v.get_inner_expression())),
+            Resolved::Expr(..) => None,
+            Resolved::Json(value) => match value {
+                serde_json::Value::Object(map) => map.get(key),
+                serde_json::Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => None,
+            }
+            .map(Resolved::Json),
+            Resolved::Namespace(scope) if self.scope_has_export(scope, key, 0) => {
+                return self.resolve_export(scope, key, offset, 0).map(Some);
+            }
+            Resolved::Namespace(_) => None,
+        };
+        Ok(found)
+    }
+
+    /// The property `key` of an object spread into a const object, looked up
+    /// in the scope of the module that declares it.
+    fn spread_member(&self, argument: &'a Expression<'a>, key: &str, offset: u32) -> Result<Option<Resolved<'b, 'a>>> {
+        let spread = match argument.get_inner_expression() {
+            literal @ Expression::ObjectExpression(_) => Some(Resolved::Expr(self.module, None, literal)),
+            argument => self.resolve_object(argument)?,
+        };
+        match spread {
+            Some(spread) => self.find_member(spread, key, offset),
+            None => Err(self.error_at(argument.span().start, format!(
+                "css() — '...{}' is not a static object, so '{}' cannot be looked up through it.\n\
+                 Hint: spread a const object, an imported one or a theme value.",
+                self.source_text(argument.span()), key
+            ))),
+        }
+    }
+
+    /// Run `f` with the binding pushed on the evaluation stack, reporting a
+    /// circular reference if it is already being evaluated.
+    fn with_symbol<T>(
+        &self,
+        module: &'b ModuleScope<'b, 'a>,
+        symbol: Option<SymbolId>,
+        offset: u32,
+        f: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        let Some(symbol) = symbol else { return f() };
+        let name = module.scoping.symbol_name(symbol);
+        let key = (module.index, symbol);
+        let cycle_start = self.graph.stack.borrow().iter().position(|k| (k.0, k.1) == key);
+        if let Some(pos) = cycle_start {
+            let stack = self.graph.stack.borrow();
+            let cycle: Vec<&str> =
+                stack[pos..].iter().map(|k| k.2.as_str()).chain(std::iter::once(name)).collect();
+            return Err(self.error_at(offset, format!(
+                "css() — circular constant reference: {}.",
+                cycle.join(" → ")
+            )));
+        }
+        self.graph.stack.borrow_mut().push((key.0, key.1, name.to_string()));
+        let result = f();
+        self.graph.stack.borrow_mut().pop();
+        result
+    }
+}
+
+/// A conditional or logical expression reduced to the operand it selects.
+enum Selected<'e> {
+    /// The selected operand, not yet evaluated (it may be a style object)
+    Expr(&'e Expression<'e>),
+    /// A short-circuiting left operand, already evaluated
+    Value(ThemeValue),
+}
+
+/// Statically pick the operand of `c ? a : b`, `a && b`, `a || b` or `a ?? b`
+/// that the expression evaluates to. Anything else selects itself.
+fn select_branch<'e>(expr: &'e Expression<'e>, ctx: &EvalCtx) -> Result<Selected<'e>> {
+    let expr = expr.get_inner_expression();
+    match expr {
+        Expression::ConditionalExpression(cond) => {
+            let branch = if eval_value_expr(&cond.test, ctx)?.is_truthy() {
+                &cond.consequent
+            } else {
+                &cond.alternate
+            };
+            select_branch(branch, ctx)
+        }
+        Expression::LogicalExpression(logical) => {
+            let left = eval_value_expr(&logical.left, ctx)?;
+            let short_circuits = match logical.operator {
+                LogicalOperator::And => !left.is_truthy(),
+                LogicalOperator::Or => left.is_truthy(),
+                LogicalOperator::Coalesce => !left.is_nullish(),
+            };
+            if short_circuits {
+                Ok(Selected::Value(left))
+            } else {
+                select_branch(&logical.right, ctx)
+            }
+        }
+        other => Ok(Selected::Expr(other)),
+    }
+}
+
+/// Evaluate an expression that may reference the theme object, a module-level
+/// const, or be a plain literal. Returns ThemeValue if it can be statically
+/// resolved, or an error.
+fn eval_value_expr(expr: &Expression, ctx: &EvalCtx) -> Result<ThemeValue> {
+    let (theme, filename, source) = (ctx.theme, ctx.filename, ctx.source);
+    // Parentheses and TS-only wrappers (`as`, `satisfies`, `!`) are transparent
+    let expr = expr.get_inner_expression();
+    match expr {
+        Expression::StringLiteral(s) => Ok(ThemeValue::Str(s.value.to_string())),
+        Expression::NumericLiteral(n) => Ok(ThemeValue::Num(n.value)),
+        Expression::BooleanLiteral(b) => Ok(ThemeValue::Bool(b.value)),
+        Expression::NullLiteral(_) => Ok(ThemeValue::Null),
+        // `const FONT_STACK = ['Inter', 'sans-serif']`
+        Expression::ArrayExpression(arr) => arr
+            .elements
+            .iter()
+            .map(|element| match element.as_expression() {
+                Some(item) => eval_value_expr(item, ctx),
+                None => Err(ctx.error_at(element.span().start, format!(
+                    "css() — '{}' in a list is not supported; list entries must be values.",
+                    ctx.source_text(element.span())
+                ))),
+            })
+            .collect::<Result<_>>()
+            .map(ThemeValue::List),
+
+        // Ternaries and short-circuiting operators: only the selected operand
+        // is evaluated
+        Expression::ConditionalExpression(_) | Expression::LogicalExpression(_) => {
+            match select_branch(expr, ctx)? {
+                Selected::Value(value) => Ok(value),
+                Selected::Expr(branch) => eval_value_expr(branch, ctx),
+            }
+        }
+
+        Expression::UnaryExpression(unary) if unary.operator == UnaryOperator::LogicalNot => {
+            Ok(ThemeValue::Bool(!eval_value_expr(&unary.argument, ctx)?.is_truthy()))
+        }
+
+        // Unary minus and plus: `-theme.spacing.unit`, `-'1rem'`
+        Expression::UnaryExpression(unary)
+            if matches!(unary.operator, UnaryOperator::UnaryNegation | UnaryOperator::UnaryPlus) =>
+        {
+            let value = eval_value_expr(&unary.argument, ctx)?;
+            match Quantity::parse(&value) {
+                _ if unary.operator == UnaryOperator::UnaryPlus => match value.to_number() {
+                    n if n.is_nan() => Err(ctx.error_at(unary.span.start, format!(
+                        "css() — unary plus of {} is not a number.",
+                        describe_value(&value)
+                    ))),
+                    n => Ok(ThemeValue::Num(n)),
+                },
+                Some(Quantity::Num(n)) => Ok(ThemeValue::Num(-n)),
+                Some(Quantity::Dim(n, unit)) => Ok(Quantity::Dim(-n, unit).into_value()),
+                Some(Quantity::Calc(term)) => Ok(Quantity::Calc(format!("(-1 * {})", term)).into_value()),
+                None => Err(ctx.error_at(unary.span.start, format!(
+                    "css() — unary minus is only supported on numbers and dimensions, got {}.",
+                    describe_value(&value)
+                ))),
+            }
+        }
+
+        // Binary expressions: arithmetic and comparisons on theme values
+        Expression::BinaryExpression(bin) => {
+            let left = eval_value_expr(&bin.left, ctx)?;
+            let right = eval_value_expr(&bin.right, ctx)?;
+            match bin.operator {
+                op @ (BinaryOperator::Addition
+                | BinaryOperator::Subtraction
+                | BinaryOperator::Multiplication
+                | BinaryOperator::Division
+                | BinaryOperator::Remainder
+                | BinaryOperator::Exponential) => eval_arithmetic(op, left, right, bin.span.start, ctx),
+                BinaryOperator::StrictEquality => Ok(ThemeValue::Bool(left == right)),
+                BinaryOperator::StrictInequality => Ok(ThemeValue::Bool(left != right)),
+                BinaryOperator::Equality => Ok(ThemeValue::Bool(left.loose_eq(&right))),
+                BinaryOperator::Inequality => Ok(ThemeValue::Bool(!left.loose_eq(&right))),
+                op @ (BinaryOperator::LessThan
+                | BinaryOperator::LessEqualThan
+                | BinaryOperator::GreaterThan
+                | BinaryOperator::GreaterEqualThan) => {
+                    let ordering = match (&left, &right) {
+                        (ThemeValue::Str(a), ThemeValue::Str(b)) => Some(a.cmp(b)),
+                        _ => left.to_number().partial_cmp(&right.to_number()),
+                    };
+                    let result = match op {
+                        BinaryOperator::LessThan => ordering == Some(Ordering::Less),
+                        BinaryOperator::LessEqualThan => {
+                            matches!(ordering, Some(Ordering::Less | Ordering::Equal))
+                        }
+                        BinaryOperator::GreaterThan => ordering == Some(Ordering::Greater),
+                        _ => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                    };
+                    Ok(ThemeValue::Bool(result))
+                }
+                _ => {
+                    let (line, col) = byte_offset_to_line_col(source, bin.span.start);
+                    Err(Error::new(Status::InvalidArg, format!(
+                        "{}:{}:{}: css() — unsupported binary operator in theme expression.",
+                        filename, line, col
+                    )))
+                }
+            }
+        }
+
+        // Template literals: only static parts + theme member interpolations
+        Expression::TemplateLiteral(tpl) => {
+            let mut result = String::new();
+            for (i, quasi) in tpl.quasis.iter().enumerate() {
+                result.push_str(quasi.value.raw.as_str());
+                if i < tpl.expressions.len() {
+                    result.push_str(&eval_value_expr(&tpl.expressions[i], ctx)?.to_js_string());
+                }
+            }
+            Ok(ThemeValue::Str(result))
+        }
+
+        // Identifiers and member paths: module-level consts first, then the theme
+        Expression::Identifier(_)
+        | Expression::MetaProperty(_)
+        | Expression::StaticMemberExpression(_)
+        | Expression::ComputedMemberExpression(_) => {
+            if let Some(resolved) = ctx.resolve_path(expr)? {
+                return eval_resolved(resolved, expr, ctx);
+            }
+            if let Expression::Identifier(id) = expr {
+                if id.name == "undefined" {
+                    return Ok(ThemeValue::Undefined);
+                }
+                // A keyframes binding extracted earlier in this file, e.g.
+                // one declared inside a component: `animationName: fadeIn`
+                if let Some(kf_name) = ctx.keyframe_name(id) {
+                    return Ok(ThemeValue::Str(kf_name.clone()));
+                }
+            }
+
+            // Computed member access (e.g. theme.colors[dynamicKey]) — explicit error
+            let chain = collect_member_chain(expr).ok_or_else(|| {
+                let (line, col) = byte_offset_to_line_col(source, expr.span().start);
+                Error::new(Status::InvalidArg, format!(
+                    "{}:{}:{}: css() — computed member access (e.g. theme.colors[key]) is not \
+                     supported. Use a static property name.\n\
+                     Hint: extract the value to a constant or use a CSS variable.",
+                    filename, line, col
+                ))
+            })?;
+
+            // Check if the chain starts with "theme"
+            if chain.first() == Some(&"theme") {
+                let theme_obj = theme.ok_or_else(|| {
+                    let (line, col) = byte_offset_to_line_col(source, expr.span().start);
+                    Error::new(Status::InvalidArg, format!(
+                        "{}:{}:{}: css() — 'theme' is referenced but no theme was provided to \
+                         the plugin.\n\
+                         Hint: add a theme to taiko({{ theme: yourTheme }}) in vite.config.js.",
+                        filename, line, col
+                    ))
+                })?;
+                let parts = &chain[1..]; // skip "theme"
+                resolve_theme_member(theme_obj, parts, filename, expr.span().start, source)
+            } else if let Some((defined, rest)) = lookup_define(ctx.defines, &chain) {
+                // `process.env.NODE_ENV`, `import.meta.env.VITE_BRAND`, … — a
+                // missing key below a defined object is `undefined`, as at runtime
+                match rest.iter().try_fold(defined, |value, key| value.get(key)) {
+                    Some(value) => ThemeValue::from_json(value).ok_or_else(|| not_a_scalar_error(expr, ctx)),
+                    None => Ok(ThemeValue::Undefined),
+                }
+            } else {
+                // A plain identifier that's not "theme" — dynamic, not supported
+                let (line, col) = byte_offset_to_line_col(source, expr.span().start);
+                Err(Error::new(
+                    Status::InvalidArg,
+                    format!(
+                        "{}:{}:{}: css() — only static values are supported \
+                         (identifier '{}' is a runtime variable).\n\
+                         Hint: extract the value to a constant or use a CSS variable.",
+                        filename, line, col,
+                        chain.first().unwrap_or(&"?")
+                    ),
+                ))
+            }
+        }
+
+        // Compile-time helpers: rem(16), fluid(14, 20), Math.max(a, b), …
+        Expression::CallExpression(call) => eval_helper_call(call, ctx),
+
+        // A const object used where a single value is expected
+        Expression::ObjectExpression(_) => {
+            Err(ctx.error_at(expr.span().start, format!(
+                "css() — '{}' is an object; only string, number and list values can be \
+                 used here.",
+                ctx.source_text(expr.span())
+            )))
+        }
+
+        other => {
+            let (line, col) = byte_offset_to_line_col(source, other.span().start);
+            Err(Error::new(
+                Status::InvalidArg,
+                format!(
+                    "{}:{}:{}: css() — only static values are supported \
+                     (property: dynamic expression).\n\
+                     Hint: extract the value to a constant or use a CSS variable.",
+                    filename, line, col
+                ),
+            ))
+        }
+    }
+}
+
+/// Evaluate what an identifier or member path resolved to. Expressions are
+/// evaluated in the scope of the module that declares them; a `keyframes`
+/// template or call evaluates to its animation name.
+fn eval_resolved<'b, 'a>(
+    resolved: Resolved<'b, 'a>,
+    expr: &Expression,
+    ctx: &EvalCtx<'b, 'a>,
+) -> Result<ThemeValue> {
+    match resolved {
+        Resolved::Expr(module, symbol, init) => {
+            let inner = ctx.for_module(module);
+            ctx.with_symbol(module, symbol, expr.span().start, || {
+                if let Expression::TaggedTemplateExpression(tagged) = init {
+                    if module.api.resolve(&tagged.tag) == Some(ApiFn::Keyframes) {
+                        let body = tagged_template_body(&tagged.quasi, &inner)?;
+                        return Ok(ThemeValue::Str(format!("kf_{}", hash_css(&body))));
+                    }
+                }
+                if let Expression::CallExpression(call) = init {
+                    if module.api.resolve(&call.callee) == Some(ApiFn::Keyframes) {
+                        return match css_call_declarations(call, &inner)? {
+                            Ok(body) => Ok(ThemeValue::Str(format!("kf_{}", hash_css(&body)))),
+                            Err(_) => Err(ctx.error_at(expr.span().start, format!(
+                                "css() — '{}' refers to keyframes whose frames are not static, so \
+                                 its animation name is not known at build time.\n\
+                                 Hint: pass keyframes() an object of frames or a theme callback.",
+                                ctx.source_text(expr.span())
+                            ))),
+                        };
+                    }
+                }
+                eval_value_expr(init, &inner)
+            })
+        }
+        Resolved::Json(value) => {
+            ThemeValue::from_json(value).ok_or_else(|| not_a_scalar_error(expr, ctx))
+        }
+        Resolved::Namespace(_) => Err(not_a_scalar_error(expr, ctx)),
+    }
+}
+
+fn not_a_scalar_error(expr: &Expression, ctx: &EvalCtx) -> Error {
+    ctx.error_at(expr.span().start, format!(
+        "css() — '{}' is not a string, number or list, so it cannot be used as a value.\n\
+         Hint: spread a composite token (`...{}`) to expand its declarations.",
+        ctx.source_text(expr.span()), ctx.source_text(expr.span())
+    ))
+}
+
+/// Keys that open a nested rule rather than name a CSS property.
+fn is_nested_rule_key(key: &str) -> bool {
+    key.starts_with(['&', ':', '@', '.', '#', '[', '>', '+', '~', '*'])
+}
+
+/// If `expr` names a static style object (`const base = { … }`, possibly
+/// imported), serialize it in the scope of the module that declares it.
+fn static_object_to_css(expr: &Expression, indent: usize, ctx: &EvalCtx) -> Result<Option<String>> {
+    let Some(Resolved::Expr(module, symbol, Expression::ObjectExpression(obj))) =
+        ctx.resolve_object(expr)?
+    else {
+        return Ok(None);
+    };
+    let inner = ctx.for_module(module);
+    ctx.with_symbol(module, symbol, expr.span().start, || object_to_css(obj, indent, &inner))
+        .map(Some)
+}
+
+// ---------------------------------------------------------------------------
+// Compile-time helpers
+//
+// Pure functions evaluated inside style values: unit conversion (`px`, `rem`,
+// `em`) and fluid typography (`fluid`) exported from `taikocss/css`, the
+// `Math.min/max/round/floor/ceil/abs` globals, and project helpers registered
+// in the plugin config as template expansions
+// (`helpers: { clampType: 'var(--font-size-{0})' }`).
+// ---------------------------------------------------------------------------
+
+/// Root font size, in px, assumed by `rem()` and `fluid()`
+const ROOT_FONT_SIZE: f64 = 16.0;
+/// Viewport range, in px, `fluid()` interpolates across by default
+const FLUID_VIEWPORT: (f64, f64) = (320.0, 1280.0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Helper {
+    Px,
+    Rem,
+    Em,
+    Fluid,
+    Darken,
+    Lighten,
+    Alpha,
+    Mix,
+    ContrastText,
+}
+
+impl Helper {
+    fn name(self) -> &'static str {
+        match self {
+            Helper::Px => "px",
+            Helper::Rem => "rem",
+            Helper::Em => "em",
+            Helper::Fluid => "fluid",
+            Helper::Darken => "darken",
+            Helper::Lighten => "lighten",
+            Helper::Alpha => "alpha",
+            Helper::Mix => "mix",
+            Helper::ContrastText => "contrastText",
+        }
+    }
+
+    /// (minimum, maximum) argument count
+    fn arity(self) -> (usize, usize) {
+        match self {
+            Helper::Px | Helper::Rem | Helper::ContrastText => (1, 1),
+            Helper::Em => (1, 2),
+            Helper::Fluid => (2, 4),
+            Helper::Darken | Helper::Lighten | Helper::Alpha => (2, 2),
+            Helper::Mix => (2, 3),
+        }
+    }
+}
+
+fn eval_helper_call(call: &CallExpression, ctx: &EvalCtx) -> Result<ThemeValue> {
+    let name = ctx.source_text(call.callee.span()).to_string();
+
+    // Project helpers registered in config: unbound identifiers only, so a
+    // local function of the same name is never shadowed
+    if let Expression::Identifier(id) = &call.callee {
+        let unbound = ctx.module.scoping.get_reference(id.reference_id()).symbol_id().is_none();
+        if let Some(template) = ctx.helpers.get(id.name.as_str()).filter(|_| unbound) {
+            return expand_helper_template(template, call, &name, ctx);
+        }
+    }
+
+    if let Some(ApiFn::Helper(helper)) = ctx.module.api.resolve(&call.callee) {
+        let (min, max) = helper.arity();
+        let args = helper_args(call, &name, min, max, ctx)?;
+        let n = |i: usize| helper_number(&args, call, i, &name, ctx);
+        let value = match helper {
+            Helper::Px => format!("{}px", format_js_number(n(0)?)),
+            Helper::Rem => format!("{}rem", format_js_number(n(0)? / ROOT_FONT_SIZE)),
+            Helper::Em => {
+                let base = if args.len() > 1 { n(1)? } else { ROOT_FONT_SIZE };
+                if base == 0.0 {
+                    return Err(ctx.error_at(call.span.start, format!(
+                        "css() — {}() base font size must not be 0.", helper.name()
+                    )));
+                }
+                format!("{}em", format_js_number(n(0)? / base))
+            }
+            Helper::Fluid => {
+                let (low, high) = (n(0)?, n(1)?);
+                let min_vw = if args.len() > 2 { n(2)? } else { FLUID_VIEWPORT.0 };
+                let max_vw = if args.len() > 3 { n(3)? } else { FLUID_VIEWPORT.1 };
+                if min_vw >= max_vw {
+                    return Err(ctx.error_at(call.span.start, format!(
+                        "css() — {}() minimum viewport ({}) must be smaller than the maximum ({}).",
+                        helper.name(), min_vw, max_vw
+                    )));
+                }
+                // Linear interpolation between (min_vw, low) and (max_vw, high)
+                let slope = (high - low) / (max_vw - min_vw);
+                let intercept = low - slope * min_vw;
+                format!(
+                    "clamp({}rem, {}rem + {}vw, {}rem)",
+                    format_js_number(low.min(high) / ROOT_FONT_SIZE),
+                    format_js_number(intercept / ROOT_FONT_SIZE),
+                    format_js_number(slope * 100.0),
+                    format_js_number(low.max(high) / ROOT_FONT_SIZE),
+                )
+            }
+            _ => colour_helper(helper, &args, call, &name, ctx)?,
+        };
+        return Ok(ThemeValue::Str(value));
+    }
+
+    if let Some(method) = math_method(&call.callee, ctx) {
+        let (min, max) = if matches!(method, "min" | "max") { (1, usize::MAX) } else { (1, 1) };
+        let args = helper_args(call, &name, min, max, ctx)?;
+        let nums = (0..args.len())
+            .map(|i| helper_number(&args, call, i, &name, ctx))
+            .collect::<Result<Vec<f64>>>()?;
+        let value = match method {
+            "min" => nums.iter().copied().fold(f64::INFINITY, f64::min),
+            "max" => nums.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            // JS rounds halves towards +∞
+            "round" => (nums[0] + 0.5).floor(),
+            "floor" => nums[0].floor(),
+            "ceil" => nums[0].ceil(),
+            _ => nums[0].abs(),
+        };
+        return Ok(ThemeValue::Num(value));
+    }
+
+    Err(ctx.error_at(call.span.start, format!(
+        "css() — only static values are supported (call to '{}' cannot be evaluated at \
+         build time).\n\
+         Hint: use a built-in helper (px, rem, em, fluid, darken, lighten, alpha, mix, \
+         contrastText, Math.min/max/round/floor/ceil/abs) \
+         or register one in taiko({{ helpers }}).",
+        name
+    )))
+}
+
+/// `Math.min` & co. on the unshadowed `Math` global → the method name.
+fn math_method<'e>(callee: &'e Expression, ctx: &EvalCtx) -> Option<&'e str> {
+    let Expression::StaticMemberExpression(mem) = callee else { return None };
+    let Expression::Identifier(obj) = &mem.object else { return None };
+    let global = ctx.module.scoping.get_reference(obj.reference_id()).symbol_id().is_none();
+    let method = mem.property.name.as_str();
+    let supported = matches!(method, "min" | "max" | "round" | "floor" | "ceil" | "abs");
+    (global && obj.name == "Math" && supported).then_some(method)
+}
+
+/// Evaluate a helper call's arguments after checking their count.
+fn helper_args(
+    call: &CallExpression,
+    name: &str,
+    min: usize,
+    max: usize,
+    ctx: &EvalCtx,
+) -> Result<Vec<ThemeValue>> {
+    let count = call.arguments.len();
+    if count < min || count > max {
+        let expected = match (min, max) {
+            (min, max) if min == max => format!("{} argument{}", min, if min == 1 { "" } else { "s" }),
+            (min, usize::MAX) => format!("at least {} argument{}", min, if min == 1 { "" } else { "s" }),
+            (min, max) => format!("{} to {} arguments", min, max),
+        };
+        return Err(ctx.error_at(call.span.start, format!(
+            "css() — {}() expects {}, got {}.",
+            name, expected, count
+        )));
+    }
+    call.arguments
+        .iter()
+        .map(|arg| match arg.as_expression() {
+            Some(expr) => eval_value_expr(expr, ctx),
+            None => Err(ctx.error_at(arg.span().start, format!(
+                "css() — spread arguments are not supported in {}().",
+                name
+            ))),
+        })
+        .collect()
+}
+
+fn helper_number(
+    args: &[ThemeValue],
+    call: &CallExpression,
+    index: usize,
+    name: &str,
+    ctx: &EvalCtx,
+) -> Result<f64> {
+    match &args[index] {
+        ThemeValue::Num(n) => Ok(*n),
+        other => Err(ctx.error_at(call.arguments[index].span().start, format!(
+            "css() — {}() argument {} must be a number, got {}.",
+            name, index + 1, describe_value(other)
+        ))),
+    }
+}
+
+fn describe_value(value: &ThemeValue) -> String {
+    match value {
+        ThemeValue::Str(s) => format!("the string '{}'", s),
+        ThemeValue::Num(n) => format!("the number {}", n),
+        ThemeValue::List(_) => "a list".to_string(),
+        other => other.to_js_string(),
+    }
+}
+
+/// Expand a config-registered helper: `{0}`, `{1}`, … are replaced by the
+/// arguments, and the highest placeholder fixes the arity.
+fn expand_helper_template(
+    template: &str,
+    call: &CallExpression,
+    name: &str,
+    ctx: &EvalCtx,
+) -> Result<ThemeValue> {
+    let arity = (0..10)
+        .rev()
+        .find(|i| template.contains(&format!("{{{}}}", i)))
+        .map_or(0, |i| i + 1);
+    let args = helper_args(call, name, arity, arity, ctx)?;
+    let mut expanded = template.to_string();
+    for (i, arg) in args.iter().enumerate() {
+        if !matches!(arg, ThemeValue::Str(_) | ThemeValue::Num(_)) {
+            return Err(ctx.error_at(call.arguments[i].span().start, format!(
+                "css() — {}() argument {} must be a string or number, got {}.",
+                name, i + 1, describe_value(arg)
+            )));
+        }
+        expanded = expanded.replace(&format!("{{{}}}", i), &arg.to_js_string());
+    }
+    Ok(ThemeValue::Str(expanded))
+}
+
+// ---------------------------------------------------------------------------
+// Colour functions
+//
+// `darken`, `lighten`, `alpha`, `mix` and `contrastText` from `taikocss/css`.
+// Colours are parsed by LightningCSS, so any CSS colour syntax is accepted.
+// Lightness and alpha are adjusted in OKLCH, mixes are interpolated in OKLab.
+// Static colours produce a concrete colour — sRGB in, sRGB out; wide-gamut
+// input stays in OKLCH/OKLab and LightningCSS adds the fallbacks. Colours only
+// known at runtime (`var()`, `currentColor`) produce `color-mix()` or relative
+// colour syntax, provided every browser target supports it.
+// ---------------------------------------------------------------------------
+
+/// First versions supporting `color-mix()`
+const COLOR_MIX_SUPPORT: Browsers = Browsers {
+    android: Some(111 << 16),
+    chrome: Some(111 << 16),
+    edge: Some(111 << 16),
+    firefox: Some(113 << 16),
+    ie: None,
+    ios_saf: Some((16 << 16) | (2 << 8)),
+    opera: Some(97 << 16),
+    safari: Some((16 << 16) | (2 << 8)),
+    samsung: Some(22 << 16),
+};
+/// First versions supporting relative colour syntax
+const RELATIVE_COLOR_SUPPORT: Browsers = Browsers {
+    android: Some(119 << 16),
+    chrome: Some(119 << 16),
+    edge: Some(119 << 16),
+    firefox: Some(128 << 16),
+    ie: None,
+    ios_saf: Some(18 << 16),
+    opera: Some(105 << 16),
+    safari: Some(18 << 16),
+    samsung: Some(25 << 16),
+};
+
+enum ColourArg {
+    /// Parsed colour, and whether it was authored in sRGB
+    Static(OKLCH, bool),
+    /// Colour only resolved by the browser, kept as written
+    Runtime(String),
+}
+
+fn colour_helper(
+    helper: Helper,
+    args: &[ThemeValue],
+    call: &CallExpression,
+    name: &str,
+    ctx: &EvalCtx,
+) -> Result<String> {
+    let colour = |i: usize| helper_colour(args, call, i, name, ctx);
+    let fraction = |i: usize| helper_fraction(args, call, i, name, ctx);
+    match helper {
+        Helper::Darken | Helper::Lighten => {
+            let amount = fraction(1)?;
+            let sign = if helper == Helper::Darken { -1.0 } else { 1.0 };
+            match colour(0)? {
+                ColourArg::Static(mut oklch, srgb) => {
+                    oklch.l = (oklch.l + (sign * amount) as f32).clamp(0.0, 1.0);
+                    print_colour(CssColor::from(oklch), srgb)
+                }
+                ColourArg::Runtime(c) => runtime_colour(
+                    format!(
+                        "oklch(from {} calc(l {} {}) c h)",
+                        c, if sign < 0.0 { '-' } else { '+' }, format_js_number(amount)
+                    ),
+                    "relative colour syntax", RELATIVE_COLOR_SUPPORT, call, name, ctx,
+                ),
+            }
+        }
+        Helper::Alpha => {
+            let opacity = fraction(1)?;
+            match colour(0)? {
+                ColourArg::Static(mut oklch, srgb) => {
+                    oklch.alpha = opacity as f32;
+                    print_colour(CssColor::from(oklch), srgb)
+                }
+                ColourArg::Runtime(c) => runtime_colour(
+                    format!("color-mix(in srgb, {} {}%, transparent)", c, format_js_number(opacity * 100.0)),
+                    "color-mix()", COLOR_MIX_SUPPORT, call, name, ctx,
+                ),
+            }
+        }
+        Helper::Mix => {
+            let weight = if args.len() > 2 { fraction(2)? } else { 0.5 };
+            match (colour(0)?, colour(1)?) {
+                (ColourArg::Static(a, a_srgb), ColourArg::Static(b, b_srgb)) => {
+                    let mixed = CssColor::from(a)
+                        .interpolate::<OKLAB>(
+                            (1.0 - weight) as f32,
+                            &CssColor::from(b),
+                            weight as f32,
+                            HueInterpolationMethod::Shorter,
+                        )
+                        .map_err(|_| ctx.error_at(call.span.start, format!(
+                            "css() — {}() could not mix these colours.", helper.name()
+                        )))?;
+                    print_colour(mixed, a_srgb && b_srgb)
+                }
+                // Both arguments are known to be colour strings by now
+                _ => runtime_colour(
+                    format!(
+                        "color-mix(in oklab, {}, {} {}%)",
+                        args[0].to_js_string(), args[1].to_js_string(), format_js_number(weight * 100.0)
+                    ),
+                    "color-mix()", COLOR_MIX_SUPPORT, call, name, ctx,
+                ),
+            }
+        }
+        _ => match colour(0)? {
+            // WCAG 2 contrast: whichever of black and white stands out more
+            ColourArg::Static(oklch, _) => {
+                let rgb = SRGBLinear::from(oklch);
+                let luminance = 0.2126 * rgb.r.clamp(0.0, 1.0)
+                    + 0.7152 * rgb.g.clamp(0.0, 1.0)
+                    + 0.0722 * rgb.b.clamp(0.0, 1.0);
+                let against_white = 1.05 / (luminance + 0.05);
+                let against_black = (luminance + 0.05) / 0.05;
+                Ok(if against_black >= against_white { "#000" } else { "#fff" }.to_string())
+            }
+            ColourArg::Runtime(c) => Err(ctx.error_at(call.arguments[0].span().start, format!(
+                "css() — {}() needs a colour known at build time, got '{}'.",
+                helper.name(), c
+            ))),
+        },
+    }
+}
+
+fn helper_colour(
+    args: &[ThemeValue],
+    call: &CallExpression,
+    index: usize,
+    name: &str,
+    ctx: &EvalCtx,
+) -> Result<ColourArg> {
+    let text = match &args[index] {
+        ThemeValue::Str(s) => s.trim(),
+        other => return Err(ctx.error_at(call.arguments[index].span().start, format!(
+            "css() — {}() argument {} must be a colour string, got {}.",
+            name, index + 1, describe_value(other)
+        ))),
+    };
+    match CssColor::parse_string(text) {
+        // currentColor, system colours and light-dark() have no value at build time
+        Ok(colour) => Ok(match OKLCH::try_from(&colour) {
+            Ok(oklch) => ColourArg::Static(oklch, matches!(colour, CssColor::RGBA(_) | CssColor::Float(_))),
+            Err(()) => ColourArg::Runtime(text.to_string()),
+        }),
+        Err(_) if text.contains("var(") => Ok(ColourArg::Runtime(text.to_string())),
+        Err(_) => Err(ctx.error_at(call.arguments[index].span().start, format!(
+            "css() — {}() argument {} is not a valid CSS colour: '{}'.",
+            name, index + 1, text
+        ))),
+    }
+}
+
+/// A number between 0 and 1: a lightness step, an opacity or a mix weight.
+fn helper_fraction(
+    args: &[ThemeValue],
+    call: &CallExpression,
+    index: usize,
+    name: &str,
+    ctx: &EvalCtx,
+) -> Result<f64> {
+    let n = helper_number(args, call, index, name, ctx)?;
+    if !(0.0..=1.0).contains(&n) {
+        return Err(ctx.error_at(call.arguments[index].span().start, format!(
+            "css() — {}() argument {} must be between 0 and 1, got {}.",
+            name, index + 1, n
+        )));
+    }
+    Ok(n)
+}
+
+fn print_colour(colour: CssColor, srgb: bool) -> Result<String> {
+    let colour = if srgb { colour.to_rgb().unwrap_or(colour) } else { colour };
+    colour.to_css_string(PrinterOptions::default()).map_err(|e| {
+        Error::new(Status::GenericFailure, format!("LightningCSS print error: {}", e))
+    })
+}
+
+/// Runtime colours pass through as CSS colour functions, which only works
+/// when every browser target understands them.
+fn runtime_colour(
+    css: String,
+    feature: &str,
+    support: Browsers,
+    call: &CallExpression,
+    name: &str,
+    ctx: &EvalCtx,
+) -> Result<String> {
+    let versions = |b: Browsers| [b.android, b.chrome, b.edge, b.firefox, b.ie, b.ios_saf, b.opera, b.safari, b.samsung];
+    let supported = versions(ctx.targets)
+        .into_iter()
+        .zip(versions(support))
+        .all(|(target, first)| target.is_none_or(|version| first.is_some_and(|first| version >= first)));
+    if supported {
+        return Ok(css);
+    }
+    Err(ctx.error_at(call.span.start, format!(
+        "css() — {}() needs a colour known at build time: var() and currentColor are only \
+         resolved at runtime, and not every browser target supports {}.\n\
+         Hint: pass a static colour such as a theme token.",
+        name, feature
+    )))
+}
+
+// ---------------------------------------------------------------------------
+// ObjectExpression → raw CSS string (recursive)
+//
+// `ctx.theme` is optional — when present, member expressions starting with
+// "theme." are resolved against it. When absent, any theme reference is an
+// error. Module-level consts are resolved either way.
+// ---------------------------------------------------------------------------
+
+/// Rendered properties of one style object in JS key order. Assigning a key
+/// that is already present replaces its entry in place, so spreads merge
+/// with the same later-wins semantics as the object literal at runtime —
+/// including nested selectors, which are replaced rather than deep-merged.
+/// Composed styles (`css(base, variant)`) are deep-merged with `merge`.
+#[derive(Default)]
+struct StyleEntries {
+    /// (JS key, entry); keyless entries come from `container()` spreads and
+    /// css`…` templates
+    entries: Vec<(Option<String>, StyleEntry)>,
+}
+
+enum StyleEntry {
+    /// Rendered CSS: declarations, or a rule that is only replaced as a whole
+    Css(String),
+    /// A nested style object `key { … }` at this indentation, kept
+    /// structured so that composed styles can merge into it
+    Rule(String, StyleEntries),
+}
+
+impl StyleEntries {
+    fn set(&mut self, key: String, entry: StyleEntry) {
+        match self.entries.iter_mut().find(|(k, _)| k.as_deref() == Some(key.as_str())) {
+            Some(existing) => existing.1 = entry,
+            None => self.entries.push((Some(key), entry)),
+        }
+    }
+
+    fn extend(&mut self, other: StyleEntries) {
+        for (key, entry) in other.entries {
+            match key {
+                Some(key) => self.set(key, entry),
+                None => self.entries.push((None, entry)),
+            }
+        }
+    }
+
+    /// Deep-merge `other` into these entries: nested rules present in both
+    /// are merged key by key, and everything else is replaced as by `extend`.
+    fn merge(&mut self, other: StyleEntries) {
+        for (key, entry) in other.entries {
+            let existing = key.as_deref().and_then(|key| {
+                self.entries.iter_mut().find(|(k, _)| k.as_deref() == Some(key))
+            });
+            match (existing, entry) {
+                (Some((_, StyleEntry::Rule(_, body))), StyleEntry::Rule(_, other_body)) => {
+                    body.merge(other_body);
+                }
+                (_, entry) => match key {
+                    Some(key) => self.set(key, entry),
+                    None => self.entries.push((None, entry)),
+                },
+            }
+        }
+    }
+
+    fn into_css(self) -> String {
+        self.entries
+            .into_iter()
+            .map(|(key, entry)| match entry {
+                StyleEntry::Css(css) => css,
+                StyleEntry::Rule(pad, body) => format!(
+                    "{}{} {{\n{}{}}}\n",
+                    pad, key.unwrap_or_default(), body.into_css(), pad
+                ),
+            })
+            .collect()
+    }
+}
+
+fn object_to_css(obj: &ObjectExpression, indent: usize, ctx: &EvalCtx) -> Result<String> {
+    Ok(object_entries(obj, indent, ctx)?.into_css())
+}
+
+fn object_entries(obj: &ObjectExpression, indent: usize, ctx: &EvalCtx) -> Result<StyleEntries> {
+    let (filename, source, api) = (ctx.filename, ctx.source, &ctx.module.api);
+    let pad = "  ".repeat(indent);
+    let mut entries = StyleEntries::default();
+
+    for prop in &obj.properties {
+        match prop {
+            ObjectPropertyKind::ObjectProperty(p) => {
+                let key_str: String = match &p.key {
+                    PropertyKey::StringLiteral(s) => s.value.to_string(),
+                    PropertyKey::StaticIdentifier(id) => id.name.to_string(),
+                    // `[`@media (min-width: ${theme.breakpoints.md})`]`, `[SELECTORS.hocus]`
+                    other => match other.as_expression() {
+                        Some(key) => computed_key(key, ctx)?,
+                        None => {
+                            let (line, col) = byte_offset_to_line_col(source, other.span().start);
+                            return Err(Error::new(
+                                Status::InvalidArg,
+                                format!(
+                                    "{}:{}:{}: css() — private property keys are not supported.\n\
+                                     Hint: use a plain string or identifier as the property name.",
+                                    filename, line, col
+                                ),
+                            ));
+                        }
+                    },
+                };
+                // `textStyle: 'heading.lg'` — a composite token from the theme
+                if let Some((_, group)) = COMPOSITE_KEYS.iter().find(|(k, _)| *k == key_str) {
+                    entries.extend(composite_token(&key_str, group, &p.value, indent, ctx)?);
+                    continue;
+                }
+                // `'_hover'`, `'_dark'` — named conditions
+                let key_str = if is_condition_key(&key_str) {
+                    condition_rule(&key_str, p.key.span().start, ctx)?.to_string()
+                } else {
+                    key_str
+                };
+                // `'@md'`, `'@between md lg'`, `'@container @md-down'`
+                let key_str = breakpoint_key(&key_str, p.key.span().start, ctx)?.unwrap_or(key_str);
+
+                let entry = property_entry(&key_str, &p.value, indent, ctx)?;
+                entries.set(key_str, entry);
+            }
+
+            ObjectPropertyKind::SpreadProperty(spread) => {
+                // Special case: container() spread is expanded in place
+                if let Expression::CallExpression(call) = spread.argument.get_inner_expression() {
+                    if is_container_call(call, api) {
+                        let expanded = expand_container_call(call, filename, source)?;
+                        entries.entries.push((None, StyleEntry::Css(format!("{}{};\n", pad, expanded))));
+                        continue;
+                    }
+                }
+                entries.extend(spread_entries(&spread.argument, indent, ctx)?);
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// One `key: value` entry. A nested style object stays structured for
+/// composition; everything else is rendered by `property_css`. Only a
+/// globalCss() object starts at indent 0, and its top-level keys are always
+/// selectors or at-rules, so `body: { _dark: { … } }` is a rule for `body`.
+fn property_entry(key_str: &str, value: &Expression, indent: usize, ctx: &EvalCtx) -> Result<StyleEntry> {
+    if let Expression::ObjectExpression(nested_obj) = value.get_inner_expression() {
+        if indent == 0 || is_nested_rule_key(key_str) || !is_responsive_value(value.get_inner_expression(), ctx) {
+            let body = object_entries(nested_obj, indent + 1, ctx)?;
+            return Ok(StyleEntry::Rule("  ".repeat(indent), body));
+        }
+    }
+    property_css(key_str, value, indent, ctx).map(StyleEntry::Css)
+}
+
+/// The CSS for one `key: value` entry: a nested rule, a declaration, or a
+/// responsive declaration with its media queries.
+fn property_css(key_str: &str, value: &Expression, indent: usize, ctx: &EvalCtx) -> Result<String> {
+    let pad = "  ".repeat(indent);
+
+    // `cond ? a : b`, `a && b`, … select their operand first, so the
+    // selected operand may itself be a nested style object
+    let value = match select_branch(value, ctx)? {
+        Selected::Expr(value) => value,
+        Selected::Value(short_circuited) => {
+            return declaration(&pad, key_str, short_circuited, value, ctx);
+        }
+    };
+
+    let css = match value {
+        // `height: fallback('100vh', '100dvh')` — one declaration per argument, in order
+        Expression::CallExpression(call)
+            if !is_nested_rule_key(key_str) && ctx.module.api.resolve(&call.callee) == Some(ApiFn::Fallback) =>
+        {
+            fallback_css(key_str, call, indent, ctx)?
+        }
+        Expression::ArrayExpression(arr) if !is_nested_rule_key(key_str) && is_ambiguous_list(key_str, arr) => {
+            return Err(ctx.error_at(arr.span.start, format!(
+                "css() — '{key}' is given an inline array, which could be responsive values or a \
+                 {prop} list.\nHint: write the list as a string or a const list, responsive values \
+                 as {{ base: …, md: … }}, and fallbacks as fallback(…).",
+                key = key_str,
+                prop = camel_to_kebab(key_str),
+            )));
+        }
+        Expression::ArrayExpression(arr) if !is_nested_rule_key(key_str) && is_string_list(arr) => {
+            return Err(ctx.error_at(arr.span.start, format!(
+                "css() — '{}' is given an inline array of strings, which could be responsive values \
+                 or fallback values.\nHint: write responsive values as {{ base: …, md: … }} or with \
+                 a null entry, and fallbacks as fallback(…).",
+                key_str
+            )));
+        }
+        // `padding: [8, 16, 24]`, `padding: { base: 8, md: 16 }`
+        Expression::ArrayExpression(_) | Expression::ObjectExpression(_)
+            if indent > 0 && !is_nested_rule_key(key_str) && is_responsive_value(value, ctx) =>
+        {
+            responsive_css(key_str, value, indent, ctx)?
+        }
+        Expression::ObjectExpression(nested_obj) => {
+            let nested_css = object_to_css(nested_obj, indent + 1, ctx)?;
+            format!("{}{} {{\n{}{}}}\n", pad, key_str, nested_css, pad)
+        }
+        Expression::StringLiteral(s) => {
+            let prop_name = camel_to_kebab(key_str);
+            format!("{}{}: {};\n", pad, prop_name, s.value)
+        }
+        // Unitless properties keep the number; everything else gets `px`
+        Expression::NumericLiteral(n) => {
+            let prop_name = camel_to_kebab(key_str);
+            format!("{}{}: {};\n", pad, prop_name, ThemeValue::Num(n.value).to_css_value(&prop_name))
+        }
+        // Template literal: resolve keyframe references + static concatenation
+        Expression::TemplateLiteral(tpl) => {
+            let prop_name = camel_to_kebab(key_str);
+            format!("{}{}: {};\n", pad, prop_name, template_to_string(tpl, ctx)?)
+        }
+        other => {
+            // A const style object, local or imported, under a selector or
+            // at-rule key: `'&:hover': hoverStyles`
+            let nested = if is_nested_rule_key(key_str) {
+                static_object_to_css(other, indent + 1, ctx)?
+            } else {
+                None
+            };
+            match nested {
+                Some(nested_css) => {
+                    format!("{}{} {{\n{}{}}}\n", pad, key_str, nested_css, pad)
+                }
+                // Always try static evaluation — handles theme members, arithmetic,
+                // template literals, and gives a "theme" error when theme is absent.
+                None => declaration(&pad, key_str, eval_value_expr(other, ctx)?, other, ctx)?,
+            }
+        }
+    };
+    Ok(css)
+}
+
+/// Whether an inline array on a comma-list property (`fontFamily: ['Inter',
+/// 'sans-serif']`) is ambiguous. A hole or `null` entry only makes sense per
+/// breakpoint, so such arrays stay responsive.
+fn is_ambiguous_list(key_str: &str, arr: &ArrayExpression) -> bool {
+    COMMA_LISTS.contains(&camel_to_kebab(key_str).as_str())
+        && arr.elements.iter().all(|element| {
+            !matches!(element, ArrayExpressionElement::Elision(_) | ArrayExpressionElement::NullLiteral(_))
+        })
+}
+
+/// Whether an inline array is made only of string literals (`height:
+/// ['100vh', '100dvh']`), which reads as fallback values as much as
+/// responsive ones. A hole or `null` entry marks it as responsive.
+fn is_string_list(arr: &ArrayExpression) -> bool {
+    !arr.elements.is_empty()
+        && arr.elements.iter().all(|element| matches!(element, ArrayExpressionElement::StringLiteral(_)))
+}
+
+/// `fallback(a, b, …)`: one declaration per argument, so browsers that reject
+/// a later value keep the earlier one.
+fn fallback_css(key_str: &str, call: &CallExpression, indent: usize, ctx: &EvalCtx) -> Result<String> {
+    if call.arguments.is_empty() {
+        return Err(ctx.error_at(call.span.start, format!(
+            "css() — fallback() for '{}' needs at least one value.", key_str
+        )));
+    }
+    call.arguments
+        .iter()
+        .map(|arg| match arg.as_expression() {
+            Some(value) => property_css(key_str, value, indent, ctx),
+            None => Err(ctx.error_at(arg.span().start, format!(
+                "css() — fallback() for '{}' takes its values as plain arguments, not a spread.", key_str
+            ))),
+        })
+        .collect()
+}
+
+/// A rendered `prop: value;` line for an evaluated property value. `false`,
+/// `null` and `undefined` render nothing, so `color: isDark && 'white'` omits
+/// the declaration (and still overrides an earlier `color` from a spread).
+fn declaration(
+    pad: &str,
+    key: &str,
+    value: ThemeValue,
+    expr: &Expression,
+    ctx: &EvalCtx,
+) -> Result<String> {
+    if value.omits_declaration() {
+        return Ok(String::new());
+    }
+    if value == ThemeValue::Bool(true) {
+        return Err(ctx.error_at(expr.span().start, format!(
+            "css() — '{}' evaluates to `true`, which is not a CSS value.\n\
+             Hint: use `cond && value` or `cond ? value : null` to apply a value conditionally.",
+            ctx.source_text(expr.span())
+        )));
+    }
+    let prop_name = camel_to_kebab(key);
+    let val = value.to_css_value(&prop_name);
+    Ok(format!("{}{}: {};\n", pad, prop_name, val))
+}
+
+/// Entries of a spread argument: an inline object, a const style object
+/// (local or imported), a theme sub-object, or a ternary / logical
+/// expression between those with a statically known condition. Spreading
+/// `false`, `null` or `undefined` adds nothing, as at runtime.
+fn spread_entries(expr: &Expression, indent: usize, ctx: &EvalCtx) -> Result<StyleEntries> {
+    let expr = match select_branch(expr, ctx)? {
+        Selected::Expr(expr) => expr,
+        Selected::Value(value) if value.omits_declaration() => return Ok(StyleEntries::default()),
+        Selected::Value(_) => return Err(spread_error(expr, ctx)),
+    };
+    match expr {
+        Expression::ObjectExpression(obj) => return object_entries(obj, indent, ctx),
+        Expression::NullLiteral(_) => return Ok(StyleEntries::default()),
+        Expression::BooleanLiteral(b) if !b.value => return Ok(StyleEntries::default()),
+        _ => {}
+    }
+
+    match ctx.resolve_object(expr)? {
+        Some(Resolved::Expr(module, symbol, init)) => {
+            let inner = ctx.for_module(module);
+            return ctx.with_symbol(module, symbol, expr.span().start, || {
+                match init {
+                    Expression::ObjectExpression(_)
+                    | Expression::ConditionalExpression(_)
+                    | Expression::LogicalExpression(_) => spread_entries(init, indent, &inner),
+                    _ => Err(spread_error(expr, ctx)),
+                }
+            });
+        }
+        Some(Resolved::Json(serde_json::Value::Object(map))) => {
+            return json_entries(map, indent, expr, ctx);
+        }
+        Some(_) => return Err(spread_error(expr, ctx)),
+        None => {}
+    }
+
+    // `...theme.typography.heading`
+    if let Some(chain) = collect_member_chain(expr) {
+        if chain.first() == Some(&"theme") {
+            if let Some(serde_json::Value::Object(map)) =
+                ctx.theme.and_then(|theme| chain[1..].iter().try_fold(theme, |v, k| v.get(k)))
+            {
+                return json_entries(map, indent, expr, ctx);
+            }
+        }
+    }
+    Err(spread_error(expr, ctx))
+}
+
+/// Concatenate a template literal's quasis and statically evaluated
+/// interpolations, naming keyframe bindings by their generated name and
+/// css() bindings by their class selector.
+fn template_to_string(tpl: &TemplateLiteral, ctx: &EvalCtx) -> Result<String> {
+    let mut val = String::new();
+    for (i, quasi) in tpl.quasis.iter().enumerate() {
+        val.push_str(quasi.value.raw.as_str());
+        if i < tpl.expressions.len() {
+            val.push_str(&interpolation_to_string(&tpl.expressions[i], ctx)?);
+        }
+    }
+    Ok(val)
+}
+
+/// Concatenate the quasis and evaluated interpolations of a css`…` or
+/// keyframes`…` template — constants, theme tokens, keyframe and css()
+/// bindings, and `${({ theme }) => …}` callbacks. The generated name is a
+/// hash of this text, so a binding evaluated from another module names the
+/// same class or animation its own file extracts.
+fn tagged_template_body(tpl: &TemplateLiteral, ctx: &EvalCtx) -> Result<String> {
+    let mut inner = String::new();
+    for (i, quasi) in tpl.quasis.iter().enumerate() {
+        inner.push_str(quasi.value.raw.as_str());
+        if i < tpl.expressions.len() {
+            let interp = &tpl.expressions[i];
+            let text = match theme_callback(interp, ctx.module.scoping) {
+                Some((returned, locals)) => {
+                    interpolation_to_string(returned, &EvalCtx { locals: Some(&locals), ..*ctx })?
+                }
+                None => interpolation_to_string(interp, ctx)?,
+            };
+            inner.push_str(&text);
+        }
+    }
+    Ok(inner)
+}
+
+/// One template interpolation as text: a keyframe binding's animation name, a
+/// css() binding's class selector or a statically evaluated value.
+fn interpolation_to_string(interp: &Expression, ctx: &EvalCtx) -> Result<String> {
+    // Check if the interpolation is a known keyframe binding
+    if let Expression::Identifier(id) = interp {
+        if let Some(kf_name) = ctx.keyframe_name(id) {
+            return Ok(kf_name.clone());
+        }
+    }
+    if let Some(selector) = class_selector(interp, ctx)? {
+        return Ok(selector);
+    }
+    // Otherwise try to evaluate as a theme value
+    Ok(eval_value_expr(interp, ctx)?.to_js_string())
+}
+
+/// Evaluate a computed property key — a property name, selector or at-rule
+/// built from constants and theme tokens.
+fn computed_key(key: &Expression, ctx: &EvalCtx) -> Result<String> {
+    if let Some(selector) = class_selector(key, ctx)? {
+        return Ok(selector);
+    }
+    let value = match key.get_inner_expression() {
+        Expression::TemplateLiteral(tpl) => return template_to_string(tpl, ctx),
+        Expression::Identifier(id) => match ctx.keyframe_name(id) {
+            Some(kf_name) => return Ok(kf_name.clone()),
+            None => eval_value_expr(key, ctx)?,
+        },
+        other => eval_value_expr(other, ctx)?,
+    };
+    match value {
+        ThemeValue::Str(_) | ThemeValue::Num(_) => Ok(value.to_js_string()),
+        other => Err(ctx.error_at(key.span().start, format!(
+            "css() — computed key '{}' evaluates to {}; keys must be strings or numbers.",
+            ctx.source_text(key.span()), describe_value(&other)
+        ))),
+    }
+}
+
+/// If `expr` names a css() class, its selector (`.cls_<hash>`). Bindings
+/// extracted earlier in this file are looked up by symbol, so a parameter or
+/// local that shadows one never matches; otherwise the
+/// `const` it resolves to — in this module or an imported one — is
+/// serialized to recompute the content-addressed class name.
+fn class_selector(expr: &Expression, ctx: &EvalCtx) -> Result<Option<String>> {
+    if let Expression::Identifier(id) = expr.get_inner_expression() {
+        if let Some(class_name) = ctx.symbol_of(id).and_then(|symbol| ctx.class_names.get(&symbol)) {
+            return Ok(Some(format!(".{}", class_name)));
+        }
+    }
+    let Some(Resolved::Expr(module, symbol, init)) = ctx.resolve_path(expr)? else {
+        return Ok(None);
+    };
+    let inner = ctx.for_module(module);
+    let declarations = match init {
+        Expression::CallExpression(call) if module.api.resolve(&call.callee) == Some(ApiFn::Css) => {
+            ctx.with_symbol(module, symbol, expr.span().start, || css_call_declarations(call, &inner))?
+        }
+        Expression::TaggedTemplateExpression(tagged) if module.api.resolve(&tagged.tag) == Some(ApiFn::Css) => {
+            ctx.with_symbol(module, symbol, expr.span().start, || tagged_template_body(&tagged.quasi, &inner))
+                .map(Ok)?
+        }
+        _ => return Ok(None),
+    };
+    match declarations {
+        Ok(declarations) => Ok(Some(format!(".cls_{}", hash_css(&declarations)))),
+        Err(_) => Err(ctx.error_at(expr.span().start, format!(
+            "css() — '{}' refers to a css() class whose styles are not static, so its class \
+             name is not known at build time.\n\
+             Hint: pass css() a style object, a theme callback or a const style object.",
+            ctx.source_text(expr.span())
+        ))),
+    }
+}
+
+/// The error for a css(), keyframes() or globalCss() call with a part that is
+/// not static, which would otherwise be left to a runtime that returns `''`.
+fn not_static_error(call: &CallExpression, span: Span, ctx: &EvalCtx) -> Error {
+    let callee = ctx.source_text(call.callee.span());
+    if span == call.span {
+        return ctx.error_at(span.start, format!("{}() — expects a style argument.", callee));
+    }
+    ctx.error_at(span.start, format!(
+        "{}() — '{}' is not a static style, so the call cannot be compiled at build time.\n\
+         Hint: use a style object, a theme callback, a const style object or a css() class, \
+         and select between styles with conditions known at build time; combine classes at \
+         runtime with cx().",
+        callee,
+        ctx.source_text(span)
+    ))
+}
+
+/// Entries of a JSON object (a theme sub-object or a JSON module value).
+fn json_entries(
+    map: &serde_json::Map<String, serde_json::Value>,
+    indent: usize,
+    expr: &Expression,
+    ctx: &EvalCtx,
+) -> Result<StyleEntries> {
+    let pad = "  ".repeat(indent);
+    let mut entries = StyleEntries::default();
+    for (key, value) in map {
+        let entry = match value {
+            serde_json::Value::Object(nested) => {
+                StyleEntry::Rule(pad.clone(), json_entries(nested, indent + 1, expr, ctx)?)
+            }
+            other => match ThemeValue::from_json(other) {
+                Some(value @ (ThemeValue::Str(_) | ThemeValue::Num(_) | ThemeValue::List(_))) => {
+                    let prop_name = camel_to_kebab(key);
+                    StyleEntry::Css(format!("{}{}: {};\n", pad, prop_name, value.to_css_value(&prop_name)))
+                }
+                _ => {
+                    return Err(ctx.error_at(expr.span().start, format!(
+                        "css() — '{}.{}' is not a string, number, list or object, so it cannot be \
+                         spread into a style.",
+                        ctx.source_text(expr.span()), key
+                    )));
+                }
+            },
+        };
+        entries.set(key.clone(), entry);
+    }
+    Ok(entries)
+}
+
+/// Keys naming a composite token, and the theme group it is looked up in.
+const COMPOSITE_KEYS: &[(&str, &str)] = &[("textStyle", "textStyles"), ("layerStyle", "layerStyles")];
+
+/// The declarations of the composite token `textStyle: 'heading.lg'` names
+/// (`theme.textStyles.heading.lg`).
+fn composite_token(
+    key: &str,
+    group: &str,
+    value: &Expression,
+    indent: usize,
+    ctx: &EvalCtx,
+) -> Result<StyleEntries> {
+    let name = match eval_value_expr(value, ctx)? {
+        ThemeValue::Str(name) => name,
+        // `textStyle: isLarge && 'heading'`
+        other if other.omits_declaration() => return Ok(StyleEntries::default()),
+        other => {
+            return Err(ctx.error_at(value.span().start, format!(
+                "css() — {} must name a token in theme.{}, got {}.",
+                key, group, describe_value(&other)
+            )));
+        }
+    };
+    let path = format!("{}.{}", group, name);
+    let theme = ctx.theme.ok_or_else(|| {
+        ctx.error_at(value.span().start, format!(
+            "css() — {}: '{}' needs theme.{}, but no theme was provided to the plugin.\n\
+             Hint: add a theme to taiko({{ theme: yourTheme }}) in vite.config.js.",
+            key, name, path
+        ))
+    })?;
+    match path.split('.').try_fold(theme, |v, k| v.get(k)) {
+        Some(serde_json::Value::Object(map)) => {
+            // `textStyle: 'heading'` when `heading` groups `sm`, `lg`, … tokens
+            let variants: Vec<&str> = map
+                .iter()
+                .filter(|(k, v)| v.is_object() && !is_nested_rule_key(k))
+                .map(|(k, _)| k.as_str())
+                .collect();
+            if !variants.is_empty() {
+                return Err(ctx.error_at(value.span().start, format!(
+                    "css() — theme.{} groups several tokens ({}); name one, e.g. {}: '{}.{}'.",
+                    path, variants.join(", "), key, name, variants[0]
+                )));
+            }
+            json_entries(map, indent, value, ctx)
+        }
+        Some(_) => Err(ctx.error_at(value.span().start, format!(
+            "css() — theme.{} is not a composite token; {} must name an object of declarations.",
+            path, key
+        ))),
+        None => Err(ctx.error_at(value.span().start, format!(
+            "css() — theme.{} does not exist in the theme object.\n\
+             Hint: check your theme definition in vite.config.js.",
+            path
+        ))),
+    }
+}
+
+fn spread_error(expr: &Expression, ctx: &EvalCtx) -> Error {
+    ctx.error_at(expr.span().start, format!(
+        "css() — spread of '{}' is not supported: only statically known objects can be spread.\n\
+         Hint: spread a const style object, a theme sub-object, or inline the properties.",
+        ctx.source_text(expr.span())
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// API binding resolution
+//
+// A callee only counts as `css` / `globalCss` / `keyframes` / `container` when
+// it resolves (via oxc semantic analysis) to a binding imported from
+// `taikocss/css` — directly, under an alias, or through a namespace import.
+// Unbound globals and local declarations that happen to share a name are
+// left alone.
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ApiFn {
+    Css,
+    GlobalCss,
+    Keyframes,
+    Container,
+    /// Class name composition, collapsed at build time when static
+    Cx,
+    Recipe,
+    /// Fallback declarations for one property, `fallback('100vh', '100dvh')`
+    Fallback,
+    /// A compile-time helper usable inside style values
+    Helper(Helper),
+}
+
+impl ApiFn {
+    fn from_export_name(name: &str) -> Option<ApiFn> {
+        match name {
+            "css" => Some(ApiFn::Css),
+            "globalCss" => Some(ApiFn::GlobalCss),
+            "keyframes" => Some(ApiFn::Keyframes),
+            "container" => Some(ApiFn::Container),
+            "cx" => Some(ApiFn::Cx),
+            "recipe" => Some(ApiFn::Recipe),
+            "fallback" => Some(ApiFn::Fallback),
+            "px" => Some(ApiFn::Helper(Helper::Px)),
+            "rem" => Some(ApiFn::Helper(Helper::Rem)),
+            "em" => Some(ApiFn::Helper(Helper::Em)),
+            "fluid" => Some(ApiFn::Helper(Helper::Fluid)),
+            "darken" => Some(ApiFn::Helper(Helper::Darken)),
+            "lighten" => Some(ApiFn::Helper(Helper::Lighten)),
+            "alpha" => Some(ApiFn::Helper(Helper::Alpha)),
+            "mix" => Some(ApiFn::Helper(Helper::Mix)),
+            "contrastText" => Some(ApiFn::Helper(Helper::ContrastText)),
+            _ => None,
+        }
+    }
+}
+
+struct ApiBindings<'s> {
+    scoping: &'s Scoping,
+    /// Local symbol → API function, for `import { css as style } from 'taikocss/css'`
+    named: HashMap<SymbolId, ApiFn>,
+    /// Local symbols of `import * as t from 'taikocss/css'`
+    namespaces: Vec<SymbolId>,
+}
+
+impl<'s> ApiBindings<'s> {
+    fn collect(program: &Program, scoping: &'s Scoping) -> Self {
+        let mut named = HashMap::new();
+        let mut namespaces = vec![];
+
+        for stmt in &program.body {
+            let Statement::ImportDeclaration(import) = stmt else { continue };
+            if import.source.value.as_str() != API_MODULE || import.import_kind.is_type() {
+                continue;
+            }
+            for spec in import.specifiers.iter().flatten() {
+                match spec {
+                    ImportDeclarationSpecifier::ImportSpecifier(s) if !s.import_kind.is_type() => {
+                        if let Some(api) = ApiFn::from_export_name(s.imported.name().as_str()) {
+                            named.insert(s.local.symbol_id(), api);
+                        }
+                    }
+                    ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => {
+                        namespaces.push(s.local.symbol_id());
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        ApiBindings { scoping, named, namespaces }
+    }
+
+    /// Resolve a call/tag callee to the API function it refers to, if any.
+    fn resolve(&self, callee: &Expression) -> Option<ApiFn> {
+        match callee {
+            // Unresolved globals are not the API — only imported bindings are
+            Expression::Identifier(id) => {
+                let symbol = self.scoping.get_reference(id.reference_id()).symbol_id()?;
+                self.named.get(&symbol).copied()
+            }
+            // `t.css(...)` through `import * as t from 'taikocss/css'`
+            Expression::StaticMemberExpression(mem) => {
+                let Expression::Identifier(obj) = &mem.object else { return None };
+                let symbol = self.scoping.get_reference(obj.reference_id()).symbol_id()?;
+                if self.namespaces.contains(&symbol) {
+                    ApiFn::from_export_name(mem.property.name.as_str())
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Build replacements that drop `taikocss/css` import specifiers whose every
+/// value reference now sits inside an already-replaced byte range. The whole
+/// declaration is removed when nothing is left; otherwise it is rewritten
+/// with the surviving specifiers.
+fn unused_api_import_replacements(
+    program: &Program,
+    semantic: &Semantic,
+    source: &str,
+    replacements: &[(u32, u32, String)],
+) -> Vec<(u32, u32, String)> {
+    let consumed = |symbol: SymbolId| {
+        semantic.symbol_references(symbol).filter(|r| !r.is_type()).all(|r| {
+            let span = semantic.reference_span(r);
+            replacements.iter().any(|(start, end, _)| *start <= span.start && span.end <= *end)
+        })
+    };
+
+    let mut out = vec![];
+    for stmt in &program.body {
+        let Statement::ImportDeclaration(import) = stmt else { continue };
+        if import.source.value.as_str() != API_MODULE || import.import_kind.is_type() {
+            continue;
+        }
+        let Some(specifiers) = &import.specifiers else { continue };
+
+        let mut default_kept: Option<&str> = None;
+        let mut namespace_kept: Option<&str> = None;
+        let mut named_kept: Vec<&str> = vec![];
+        let mut removed_any = false;
+
+        for spec in specifiers {
+            let span = spec.span();
+            let text = &source[span.start as usize..span.end as usize];
+            match spec {
+                ImportDeclarationSpecifier::ImportSpecifier(s) => {
+                    if !s.import_kind.is_type() && consumed(s.local.symbol_id()) {
+                        removed_any = true;
+                    } else {
+                        named_kept.push(text);
+                    }
+                }
+                ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => {
+                    if consumed(s.local.symbol_id()) {
+                        removed_any = true;
+                    } else {
+                        namespace_kept = Some(text);
+                    }
+                }
+                ImportDeclarationSpecifier::ImportDefaultSpecifier(_) => {
+                    default_kept = Some(text);
+                }
+            }
+        }
+
+        if !removed_any {
+            continue;
+        }
+
+        let mut clauses: Vec<String> = vec![];
+        clauses.extend(default_kept.map(str::to_string));
+        clauses.extend(namespace_kept.map(str::to_string));
+        if !named_kept.is_empty() {
+            clauses.push(format!("{{ {} }}", named_kept.join(", ")));
+        }
+
+        let replacement = if clauses.is_empty() {
+            String::new()
+        } else {
+            let src_span = import.source.span;
+            let original = &source[import.span.start as usize..import.span.end as usize];
+            format!(
+                "import {} from {}{}",
+                clauses.join(", "),
+                &source[src_span.start as usize..src_span.end as usize],
+                if original.ends_with(';') { ";" } else { "" }
+            )
+        };
+        out.push((import.span.start, import.span.end, replacement));
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// container() call helpers
+// ---------------------------------------------------------------------------
+
+fn is_container_call(call: &CallExpression, api: &ApiBindings) -> bool {
+    api.resolve(&call.callee) == Some(ApiFn::Container)
+}
+
+/// Expand `container('sidebar', 'inline-size')` or `container('inline-size')`
+/// into the CSS property declarations string (without the selector wrapper).
+fn expand_container_call(
+    call: &CallExpression,
+    filename: &str,
+    source: &str,
+) -> Result<String> {
+    let args: Vec<_> = call.arguments.iter()
+        .filter_map(|a| a.as_expression())
+        .collect();
 
     match args.len() {
         1 => {
@@ -571,45 +2885,277 @@ fn extract_string_arg<'a>(
 }
 
 // ---------------------------------------------------------------------------
-// Process one css({}) argument → (class_name, minified_css, optional_css_map)
+// Process one css() call → (class_name, minified_css, optional_css_map)
 // ---------------------------------------------------------------------------
 
-fn process_css_object(
-    obj: &ObjectExpression,
-    _span_start: u32,
+/// The css() classes a static cx() argument refers to, in order and without
+/// repeats.
+fn cx_classes(style: &Expression, ctx: &EvalCtx, classes: &mut Vec<String>) -> Result<()> {
+    match select_branch(style, ctx)? {
+        Selected::Expr(Expression::ArrayExpression(arr)) => {
+            for item in arr.elements.iter().filter_map(ArrayExpressionElement::as_expression) {
+                cx_classes(item, ctx, classes)?;
+            }
+        }
+        Selected::Expr(style) => {
+            if let Some(selector) = class_selector(style, ctx)? {
+                let class_name = selector.trim_start_matches('.').to_string();
+                if !classes.contains(&class_name) {
+                    classes.push(class_name);
+                }
+            }
+        }
+        Selected::Value(_) => {}
+    }
+    Ok(())
+}
+
+/// Whether selecting a style reads a binding that has no value at build
+/// time — a parameter or local of the enclosing function, as in
+/// `isActive && active` or `props.variant`.
+fn depends_on_runtime(expr: &Expression, ctx: &EvalCtx) -> bool {
+    match expr.get_inner_expression() {
+        Expression::Identifier(id) => {
+            ctx.module.scoping.get_reference(id.reference_id()).symbol_id().is_some()
+                && matches!(ctx.resolve_path(expr), Ok(None))
+        }
+        Expression::StaticMemberExpression(mem) => depends_on_runtime(&mem.object, ctx),
+        Expression::ComputedMemberExpression(mem) => {
+            depends_on_runtime(&mem.object, ctx) || depends_on_runtime(&mem.expression, ctx)
+        }
+        Expression::UnaryExpression(unary) => depends_on_runtime(&unary.argument, ctx),
+        Expression::BinaryExpression(binary) => {
+            depends_on_runtime(&binary.left, ctx) || depends_on_runtime(&binary.right, ctx)
+        }
+        Expression::LogicalExpression(logical) => {
+            depends_on_runtime(&logical.left, ctx) || depends_on_runtime(&logical.right, ctx)
+        }
+        Expression::ArrayExpression(arr) => arr
+            .elements
+            .iter()
+            .filter_map(ArrayExpressionElement::as_expression)
+            .any(|item| depends_on_runtime(item, ctx)),
+        Expression::ConditionalExpression(cond) => {
+            depends_on_runtime(&cond.test, ctx)
+                || depends_on_runtime(&cond.consequent, ctx)
+                || depends_on_runtime(&cond.alternate, ctx)
+        }
+        _ => false,
+    }
+}
+
+/// A static style, or the span of the first part of it that is not static.
+type StaticStyle<T> = std::result::Result<T, Span>;
+
+/// Serialize the declarations of a css() or keyframes() call, or the span of
+/// the first argument that is not a static style.
+fn css_call_declarations(call: &CallExpression, ctx: &EvalCtx) -> Result<StaticStyle<String>> {
+    Ok(css_call_entries(call, ctx)?.map(StyleEntries::into_css))
+}
+
+/// Entries of a css() call: css({ ... }), css(({ theme }) => ({ ... })),
+/// css(baseStyles) or a composition of several such styles —
+/// css(base, variant, { ... }), css([base, isDense && dense]) — deep-merged
+/// in order, later arguments winning.
+fn css_call_entries(call: &CallExpression, ctx: &EvalCtx) -> Result<StaticStyle<StyleEntries>> {
+    if call.arguments.is_empty() {
+        return Ok(Err(call.span));
+    }
+    let mut merged = StyleEntries::default();
+    for arg in &call.arguments {
+        let Some(style) = arg.as_expression() else { return Ok(Err(arg.span())) };
+        match style_entries(style, ctx)? {
+            Ok(entries) => merged.merge(entries),
+            Err(span) => return Ok(Err(span)),
+        }
+    }
+    Ok(Ok(merged))
+}
+
+/// Entries of one composed style: a style object, a theme callback, an array
+/// of styles, a statically selected `cond && styles` operand, or a reference
+/// to a const style object or another css() class — in this module or an
+/// imported one. `false`, `null` and `undefined` contribute nothing. Returns
+/// the span of the first part that is not a static style.
+fn style_entries(style: &Expression, ctx: &EvalCtx) -> Result<StaticStyle<StyleEntries>> {
+    // Function form is evaluated with the callback's bindings in scope
+    if let Some((returned, locals)) = theme_callback(style.get_inner_expression(), ctx.module.scoping) {
+        return style_entries(returned, &EvalCtx { locals: Some(&locals), ..*ctx });
+    }
+    let style = match select_branch(style, ctx)? {
+        Selected::Expr(style) => style,
+        Selected::Value(value) if value.omits_declaration() => return Ok(Ok(StyleEntries::default())),
+        Selected::Value(_) => return Ok(Err(style.span())),
+    };
+    match style {
+        Expression::ObjectExpression(obj) => return object_entries(obj, 1, ctx).map(Ok),
+        Expression::NullLiteral(_) => return Ok(Ok(StyleEntries::default())),
+        Expression::BooleanLiteral(b) if !b.value => return Ok(Ok(StyleEntries::default())),
+        Expression::ArrayExpression(arr) => {
+            let mut merged = StyleEntries::default();
+            for element in &arr.elements {
+                match element {
+                    ArrayExpressionElement::Elision(_) => {}
+                    element => match element.as_expression() {
+                        Some(item) => match style_entries(item, ctx)? {
+                            Ok(entries) => merged.merge(entries),
+                            Err(span) => return Ok(Err(span)),
+                        },
+                        None => return Ok(Err(element.span())),
+                    },
+                }
+            }
+            return Ok(Ok(merged));
+        }
+        // An inline css() call or template: `cx(css({ … }), …)`
+        Expression::CallExpression(call) if ctx.module.api.resolve(&call.callee) == Some(ApiFn::Css) => {
+            return css_call_entries(call, ctx);
+        }
+        Expression::TaggedTemplateExpression(tagged) if ctx.module.api.resolve(&tagged.tag) == Some(ApiFn::Css) => {
+            return template_entries(tagged, ctx).map(Ok);
+        }
+        _ => {}
+    }
+
+    match ctx.resolve_object(style)? {
+        // A reference reports itself, not the part of its declaration (possibly
+        // in another module) that is not static
+        Some(Resolved::Expr(module, symbol, init)) => {
+            let inner = ctx.for_module(module);
+            let entries = ctx.with_symbol(module, symbol, style.span().start, || match init {
+                Expression::ObjectExpression(_)
+                | Expression::ArrayExpression(_)
+                | Expression::ConditionalExpression(_)
+                | Expression::LogicalExpression(_) => style_entries(init, &inner),
+                Expression::CallExpression(call) if module.api.resolve(&call.callee) == Some(ApiFn::Css) => {
+                    css_call_entries(call, &inner)
+                }
+                Expression::TaggedTemplateExpression(tagged) if module.api.resolve(&tagged.tag) == Some(ApiFn::Css) => {
+                    template_entries(tagged, &inner).map(Ok)
+                }
+                _ => Ok(Err(init.span())),
+            })?;
+            Ok(entries.map_err(|_| style.span()))
+        }
+        Some(Resolved::Json(serde_json::Value::Object(map))) => json_entries(map, 1, style, ctx).map(Ok),
+        Some(_) => Ok(Err(style.span())),
+        // `theme.layerStyles.card`
+        None => match collect_member_chain(style) {
+            Some(chain) if chain.first() == Some(&"theme") => {
+                match ctx.theme.and_then(|theme| chain[1..].iter().try_fold(theme, |v, k| v.get(k))) {
+                    Some(serde_json::Value::Object(map)) => json_entries(map, 1, style, ctx).map(Ok),
+                    _ => Ok(Err(style.span())),
+                }
+            }
+            _ => Ok(Err(style.span())),
+        },
+    }
+}
+
+/// A css`…` template as a single entry. Its text is not split into keys, so
+/// later styles override it by coming after it in the rule.
+fn template_entries(tagged: &TaggedTemplateExpression, ctx: &EvalCtx) -> Result<StyleEntries> {
+    let body = tagged_template_body(&tagged.quasi, ctx)?;
+    Ok(StyleEntries { entries: vec![(None, StyleEntry::Css(body))] })
+}
+
+/// Serialized declarations → (class_name, minified_css, optional_css_map)
+fn css_object_rule(inner: &str, filename: &str, output: &CssOutput) -> Result<(String, String, Option<String>)> {
+    css_rule_at_tier(inner, 1, filename, output)
+}
+
+/// A class rule whose selector repeats the class `tier` times (`.cls_x.cls_x`),
+/// so it overrides the same declarations of lower-tier classes on the element
+/// whatever order the stylesheets load in. Tier 1 is a plain css() class;
+/// higher tiers hash their selector too, so the same styles get a distinct
+/// class per tier.
+fn css_rule_at_tier(
+    inner: &str,
+    tier: usize,
     filename: &str,
-    source: &str,
-    theme: Option<&serde_json::Value>,
-    keyframe_names: &HashMap<String, String>,
-    dir: &str,
+    output: &CssOutput,
 ) -> Result<(String, String, Option<String>)> {
     // 1. Build raw CSS using a placeholder class name
-    let inner = object_to_css(obj, 1, filename, source, theme, keyframe_names)?;
-    let raw_css = format!(".css_obj {{\n{}}}\n", inner);
+    let raw_css = format!("{} {{\n{}}}\n", ".css_obj".repeat(tier), inner);
 
     // 2. Hash the CSS content to produce a stable, content-addressed class name
-    let hash = hash_css(&inner);
+    let hash = if tier == 1 { hash_css(inner) } else { hash_css(&raw_css) };
     let class_name = format!("cls_{}", hash);
 
-    process_raw_css_with_placeholder(&raw_css, &class_name, ".css_obj", filename, dir)
+    process_raw_css_with_placeholder(&raw_css, &class_name, ".css_obj", filename, output)
+}
+
+/// How generated CSS is printed: the text direction and the browsers it must
+/// work in.
+struct CssOutput<'o> {
+    /// "ltr" (default) or "rtl"
+    dir: &'o str,
+    targets: Browsers,
+}
+
+/// Container-query-aware browser targets (Chrome 105+, Safari 16+, Firefox 110+)
+fn browser_targets() -> Browsers {
+    Browsers {
+        chrome:  Some(105 << 16),
+        safari:  Some(16 << 16),
+        firefox: Some(110 << 16),
+        ..Browsers::default()
+    }
+}
+
+/// Browser targets from config, browser → minimum version; without config,
+/// the defaults above.
+fn parse_targets(targets: Option<HashMap<String, String>>, filename: &str) -> Result<Browsers> {
+    let Some(targets) = targets else {
+        return Ok(browser_targets());
+    };
+    let mut browsers = Browsers::default();
+    for (browser, version) in targets {
+        let slot = match browser.as_str() {
+            "android" => &mut browsers.android,
+            "chrome" => &mut browsers.chrome,
+            "edge" => &mut browsers.edge,
+            "firefox" => &mut browsers.firefox,
+            "ie" => &mut browsers.ie,
+            "ios_saf" => &mut browsers.ios_saf,
+            "opera" => &mut browsers.opera,
+            "safari" => &mut browsers.safari,
+            "samsung" => &mut browsers.samsung,
+            _ => return Err(Error::new(Status::InvalidArg, format!(
+                "{}: targets — unknown browser '{}'. Browsers: android, chrome, edge, firefox, ie, \
+                 ios_saf, opera, safari, samsung.",
+                filename, browser
+            ))),
+        };
+        *slot = Some(browser_version(&version).ok_or_else(|| {
+            Error::new(Status::InvalidArg, format!(
+                "{}: targets — '{}' is not a version for '{}'; expected e.g. \"16.2\".",
+                filename, version, browser
+            ))
+        })?);
+    }
+    Ok(browsers)
+}
+
+/// `"16.2"` → LightningCSS's `major << 16 | minor << 8 | patch` encoding.
+fn browser_version(version: &str) -> Option<u32> {
+    let parts: Vec<u32> = version.trim().split('.').map(|part| part.parse().ok()).collect::<Option<_>>()?;
+    if parts.len() > 3 || parts[1..].iter().any(|&part| part > 255) {
+        return None;
+    }
+    let part = |i: usize| parts.get(i).copied().unwrap_or(0);
+    Some((part(0) << 16) | (part(1) << 8) | part(2))
 }
 
 /// Shared LightningCSS pipeline: parse → minify → print → replace placeholder
-/// `dir` is "ltr" (default) or "rtl" — passed to LightningCSS PrinterOptions.
 /// Returns (final_css, css_map_json)
 fn run_lightningcss(
     raw_css: &str,
     filename: &str,
-    dir: &str,
+    output: &CssOutput,
 ) -> Result<(String, Option<String>)> {
-    // Container-query-aware browser targets (Chrome 105+, Safari 16+, Firefox 110+)
     let targets = Targets {
-        browsers: Some(Browsers {
-            chrome:  Some(105 << 16),
-            safari:  Some(16 << 16),
-            firefox: Some(110 << 16),
-            ..Browsers::default()
-        }),
+        browsers: Some(output.targets),
         ..Targets::default()
     };
 
@@ -621,14 +3167,17 @@ fn run_lightningcss(
         )
     })?;
 
-    stylesheet.minify(MinifyOptions::default()).map_err(|e| {
+    // The minifier merges duplicate declarations; with the targets it keeps
+    // the fallbacks those browsers need (`height: 100vh; height: 100dvh`)
+    let minify_options = MinifyOptions { targets, ..MinifyOptions::default() };
+    stylesheet.minify(minify_options).map_err(|e| {
         Error::new(
             Status::GenericFailure,
             format!("{}: LightningCSS minify error: {:?}", filename, e),
         )
     })?;
 
-    let _ = dir; // reserved for future LightningCSS direction support
+    let _ = output.dir; // reserved for future LightningCSS direction support
     let mut css_source_map = SourceMap::new("/");
     let printer_options = PrinterOptions {
         minify: true,
@@ -657,164 +3206,850 @@ fn process_raw_css_with_placeholder(
     final_name: &str,
     placeholder: &str,
     filename: &str,
-    dir: &str,
+    output: &CssOutput,
 ) -> Result<(String, String, Option<String>)> {
-    let (css_code, css_map) = run_lightningcss(raw_css, filename, dir)?;
+    let (css_code, css_map) = run_lightningcss(raw_css, filename, output)?;
     let final_css = css_code.replace(placeholder, &format!(".{}", final_name));
     Ok((final_name.to_string(), final_css, css_map))
 }
 
 // ---------------------------------------------------------------------------
-// Process a globalCss tagged template literal
+// Process globalCss — tagged template or object form
+// ---------------------------------------------------------------------------
+
+fn process_global_css_template(
+    tpl: &TemplateLiteral,
+    ctx: &EvalCtx,
+    output: &CssOutput,
+) -> Result<(String, String, Option<String>)> {
+    // Concatenate quasis and (static) expressions
+    let mut raw = String::new();
+    for (i, quasi) in tpl.quasis.iter().enumerate() {
+        raw.push_str(quasi.value.raw.as_str());
+        if i < tpl.expressions.len() {
+            let interp = &tpl.expressions[i];
+            match interp {
+                Expression::StringLiteral(s) => raw.push_str(&s.value),
+                Expression::NumericLiteral(n) => raw.push_str(&format_js_number(n.value)),
+                // Theme tokens and module-level consts
+                _ => raw.push_str(&eval_value_expr(interp, ctx)?.to_js_string()),
+            }
+        }
+    }
+    global_css_rule(&raw, ctx.filename, output)
+}
+
+/// Serialize the rules of a globalCss() call: globalCss({ body: { … } }),
+/// globalCss(({ theme }) => ({ … })) or globalCss(resetStyles). Top-level
+/// keys are selectors and at-rules, not class-scoped nested rules. Returns
+/// `None` when the argument is not a static style object.
+fn global_css_rules(call: &CallExpression, ctx: &EvalCtx) -> Result<Option<String>> {
+    let Some(arg_expr) = call.arguments.first().and_then(|a| a.as_expression()) else {
+        return Ok(None);
+    };
+    let callback = theme_callback(arg_expr.get_inner_expression(), ctx.module.scoping);
+    let (styles, ctx) = match &callback {
+        Some((returned, locals)) => (*returned, EvalCtx { locals: Some(locals), ..*ctx }),
+        None => (arg_expr, *ctx),
+    };
+    match styles.get_inner_expression() {
+        Expression::ObjectExpression(obj) => {
+            // A declaration at the top level has no rule to apply to
+            for prop in &obj.properties {
+                let ObjectPropertyKind::ObjectProperty(p) = prop else { continue };
+                if matches!(
+                    p.value.get_inner_expression(),
+                    Expression::StringLiteral(_) | Expression::NumericLiteral(_) | Expression::TemplateLiteral(_)
+                ) {
+                    return Err(ctx.error_at(p.span.start, format!(
+                        "globalCss() — '{}' is a declaration at the top level; top-level keys are \
+                         selectors or at-rules.\n\
+                         Hint: nest it under a selector, e.g. `body: {{ {} }}` or `':root': {{ … }}`.",
+                        ctx.source_text(p.key.span()), ctx.source_text(p.span)
+                    )));
+                }
+            }
+            object_to_css(obj, 0, &ctx).map(Some)
+        }
+        styles => static_object_to_css(styles, 0, &ctx),
+    }
+}
+
+/// Global CSS text → (hash, minified_css, optional_css_map)
+fn global_css_rule(raw: &str, filename: &str, output: &CssOutput) -> Result<(String, String, Option<String>)> {
+    let hash = hash_css(raw);
+    let (css_code, css_map) = run_lightningcss(raw, filename, output)?;
+    Ok((hash, css_code, css_map))
+}
+
+// ---------------------------------------------------------------------------
+// Process keyframes — tagged template or object form
+// ---------------------------------------------------------------------------
+
+/// Keyframes body → (hash, kf_name, minified_css, optional_css_map)
+fn keyframes_rule(
+    inner: &str,
+    filename: &str,
+    output: &CssOutput,
+) -> Result<(String, String, String, Option<String>)> {
+    // Wrap in @keyframes with placeholder
+    let placeholder_name = "__kf_placeholder__";
+    let raw_css = format!("@keyframes {} {{ {} }}", placeholder_name, inner.trim());
+
+    let hash = hash_css(inner);
+    let kf_name = format!("kf_{}", hash);
+
+    let (css_code, css_map) = run_lightningcss(&raw_css, filename, output)?;
+    let final_css = css_code.replace(placeholder_name, &kf_name);
+
+    Ok((hash, kf_name, final_css, css_map))
+}
+
+// ---------------------------------------------------------------------------
+// Recipes
+//
+// recipe({ base, variants, compoundVariants, defaultVariants }) compiles to
+// one class per style — the base, each variant value and each compound
+// variant — and the call is replaced with a small function that picks the
+// classes for its props. Variant values are object keys, so they are strings:
+// `true`/`false` keys make a boolean variant.
+// ---------------------------------------------------------------------------
+
+/// A recipe's serialized styles, in source order.
+#[derive(Default)]
+struct RecipeParts {
+    base: Option<String>,
+    /// (group, [(value, declarations)])
+    variants: Vec<(String, Vec<(String, String)>)>,
+    compounds: Vec<CompoundVariant>,
+    /// (group, value, offset)
+    defaults: Vec<(String, String, u32)>,
+}
+
+struct CompoundVariant {
+    /// (group, accepted values, offset)
+    conditions: Vec<(String, Vec<String>, u32)>,
+    declarations: String,
+}
+
+const RECIPE_HINT: &str = "Hint: recipe({ base, variants: { size: { sm: {…}, lg: {…} } }, \
+                           compoundVariants: [{ size: 'lg', css: {…} }], defaultVariants: { size: 'sm' } })";
+
+/// Evaluate a recipe() config — an object literal, or a theme callback
+/// returning one — and validate that compound and default variants name
+/// existing variant values.
+fn recipe_parts(call: &CallExpression, ctx: &EvalCtx) -> Result<RecipeParts> {
+    let Some(arg) = call.arguments.first().and_then(|a| a.as_expression()) else {
+        return Err(ctx.error_at(call.span.start, format!(
+            "recipe() — expected a config object.\n{}", RECIPE_HINT
+        )));
+    };
+    let callback = theme_callback(arg.get_inner_expression(), ctx.module.scoping);
+    let (config, ctx) = match &callback {
+        Some((returned, locals)) => (*returned, EvalCtx { locals: Some(locals), ..*ctx }),
+        None => (arg, *ctx),
+    };
+
+    let mut parts = RecipeParts::default();
+    for (key, value, offset) in recipe_object(config, "the config", &ctx)? {
+        match key.as_str() {
+            "base" => parts.base = Some(recipe_style(value, &ctx)?),
+            "variants" => {
+                for (group, values, _) in recipe_object(value, "`variants`", &ctx)? {
+                    let what = format!("variant '{}'", group);
+                    let values = recipe_object(values, &what, &ctx)?
+                        .into_iter()
+                        .map(|(name, style, _)| Ok((name, recipe_style(style, &ctx)?)))
+                        .collect::<Result<_>>()?;
+                    parts.variants.push((group, values));
+                }
+            }
+            "compoundVariants" => {
+                let Expression::ArrayExpression(arr) = value.get_inner_expression() else {
+                    return Err(ctx.error_at(value.span().start, format!(
+                        "recipe() — `compoundVariants` must be an array literal.\n{}", RECIPE_HINT
+                    )));
+                };
+                for element in &arr.elements {
+                    let Some(compound) = element.as_expression() else {
+                        return Err(ctx.error_at(element.span().start, format!(
+                            "recipe() — '{}' is not a compound variant object.\n{}",
+                            ctx.source_text(element.span()), RECIPE_HINT
+                        )));
+                    };
+                    let mut conditions = vec![];
+                    let mut declarations = None;
+                    for (group, condition, offset) in recipe_object(compound, "a compound variant", &ctx)? {
+                        if group == "css" {
+                            declarations = Some(recipe_style(condition, &ctx)?);
+                        } else {
+                            conditions.push((group, variant_values(condition, &ctx)?, offset));
+                        }
+                    }
+                    let Some(declarations) = declarations else {
+                        return Err(ctx.error_at(compound.span().start, format!(
+                            "recipe() — a compound variant needs a `css` key with its styles.\n{}",
+                            RECIPE_HINT
+                        )));
+                    };
+                    parts.compounds.push(CompoundVariant { conditions, declarations });
+                }
+            }
+            "defaultVariants" => {
+                for (group, default, offset) in recipe_object(value, "`defaultVariants`", &ctx)? {
+                    match variant_values(default, &ctx)?.as_slice() {
+                        [value] => parts.defaults.push((group, value.clone(), offset)),
+                        _ => {
+                            return Err(ctx.error_at(default.span().start, format!(
+                                "recipe() — the default for '{}' must be a single variant value.",
+                                group
+                            )));
+                        }
+                    }
+                }
+            }
+            other => {
+                return Err(ctx.error_at(offset, format!(
+                    "recipe() — unknown key '{}'; expected base, variants, compoundVariants or \
+                     defaultVariants.",
+                    other
+                )));
+            }
+        }
+    }
+
+    for (group, values, offset) in parts.compounds.iter().flat_map(|compound| &compound.conditions) {
+        check_variant_values(&parts, group, values, *offset, &ctx)?;
+    }
+    for (group, value, offset) in &parts.defaults {
+        check_variant_values(&parts, group, std::slice::from_ref(value), *offset, &ctx)?;
+    }
+    Ok(parts)
+}
+
+/// The statically keyed properties of an object literal:
+/// (key, value, key offset).
+fn recipe_object<'e, 'a>(
+    expr: &'e Expression<'a>,
+    what: &str,
+    ctx: &EvalCtx,
+) -> Result<Vec<(String, &'e Expression<'a>, u32)>> {
+    let Expression::ObjectExpression(obj) = expr.get_inner_expression() else {
+        return Err(ctx.error_at(expr.span().start, format!(
+            "recipe() — {} must be an object literal.\n{}", what, RECIPE_HINT
+        )));
+    };
+    obj.properties
+        .iter()
+        .map(|prop| match prop {
+            ObjectPropertyKind::ObjectProperty(p) => match p.key.static_name() {
+                Some(key) => Ok((key.to_string(), &p.value, p.key.span().start)),
+                None => Err(ctx.error_at(p.key.span().start, format!(
+                    "recipe() — keys of {} must be static names.", what
+                ))),
+            },
+            ObjectPropertyKind::SpreadProperty(spread) => Err(ctx.error_at(spread.span.start, format!(
+                "recipe() — spreads are not supported in {}; list the keys.", what
+            ))),
+        })
+        .collect()
+}
+
+/// The declarations of one recipe style.
+fn recipe_style(style: &Expression, ctx: &EvalCtx) -> Result<String> {
+    match style_entries(style, ctx)? {
+        Ok(entries) => Ok(entries.into_css()),
+        Err(span) => Err(ctx.error_at(span.start, format!(
+            "recipe() — '{}' is not a static style.\n\
+             Hint: use a style object, a theme callback, a const style object or a css() class.",
+            ctx.source_text(span)
+        ))),
+    }
+}
+
+/// A compound condition or default: a variant value or a list of them.
+fn variant_values(expr: &Expression, ctx: &EvalCtx) -> Result<Vec<String>> {
+    let value = eval_value_expr(expr, ctx)?;
+    let values = match value {
+        ThemeValue::List(items) => items,
+        scalar => vec![scalar],
+    };
+    values
+        .into_iter()
+        .map(|value| match value {
+            ThemeValue::Str(_) | ThemeValue::Num(_) | ThemeValue::Bool(_) => Ok(value.to_js_string()),
+            other => Err(ctx.error_at(expr.span().start, format!(
+                "recipe() — '{}' evaluates to {}; variant values are strings, numbers or booleans.",
+                ctx.source_text(expr.span()), describe_value(&other)
+            ))),
+        })
+        .collect()
+}
+
+fn check_variant_values(
+    parts: &RecipeParts,
+    group: &str,
+    values: &[String],
+    offset: u32,
+    ctx: &EvalCtx,
+) -> Result<()> {
+    let Some((_, defined)) = parts.variants.iter().find(|(name, _)| name == group) else {
+        let names: Vec<&str> = parts.variants.iter().map(|(name, _)| name.as_str()).collect();
+        return Err(ctx.error_at(offset, format!(
+            "recipe() — '{}' is not a variant. Variants: {}.",
+            group,
+            if names.is_empty() { "none".to_string() } else { names.join(", ") }
+        )));
+    };
+    for value in values {
+        if !defined.iter().any(|(name, _)| name == value) {
+            let names: Vec<&str> = defined.iter().map(|(name, _)| name.as_str()).collect();
+            return Err(ctx.error_at(offset, format!(
+                "recipe() — '{}' is not a value of variant '{}'. Values: {}.",
+                value, group, names.join(", ")
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// `export type ButtonVariants = { … }` for a recipe bound to `button`.
+fn recipe_types(binding_name: Option<&str>, parts: &RecipeParts) -> String {
+    let type_name = match binding_name {
+        Some(name) => {
+            let mut chars = name.chars();
+            let first = chars.next().map(|c| c.to_uppercase().to_string()).unwrap_or_default();
+            format!("{}{}Variants", first, chars.as_str())
+        }
+        None => "RecipeVariants".to_string(),
+    };
+    let mut out = format!("export type {} = {{\n", type_name);
+    for (group, values) in &parts.variants {
+        let names: Vec<&str> = values.iter().map(|(name, _)| name.as_str()).collect();
+        let ty = if names.is_empty() {
+            "never".to_string()
+        } else if names.iter().all(|name| *name == "true" || *name == "false") {
+            "boolean".to_string()
+        } else {
+            names.iter().map(|name| variant_literal(name)).collect::<Vec<_>>().join(" | ")
+        };
+        let key = if is_js_identifier(group) {
+            group.clone()
+        } else {
+            serde_json::Value::from(group.as_str()).to_string()
+        };
+        out.push_str(&format!("  {}?: {}\n", key, ty));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// A variant value as a TypeScript literal type.
+fn variant_literal(name: &str) -> String {
+    match name {
+        "true" | "false" => name.to_string(),
+        _ if name.parse::<f64>().is_ok_and(|n| format_js_number(n) == name) => name.to_string(),
+        _ => serde_json::Value::from(name).to_string(),
+    }
+}
+
+fn is_js_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+// ---------------------------------------------------------------------------
+// Theme callbacks
+//
+// css() accepts an arrow or function expression taking the theme, either as a
+// plain parameter (`(t) => …`) or destructured from its props
+// (`({ theme }) => …`, `({ theme: { colors } }) => …`). The parameter's
+// bindings and the `const`s declared before the `return` are scoped to the
+// callback: each name maps to a theme path or to its initializer.
 // ---------------------------------------------------------------------------
 
-fn process_global_css_template(
-    tpl: &TemplateLiteral,
-    filename: &str,
-    source: &str,
-    theme: Option<&serde_json::Value>,
-    dir: &str,
-) -> Result<(String, String, Option<String>)> {
-    // Concatenate quasis and (static) expressions
-    let mut raw = String::new();
-    for (i, quasi) in tpl.quasis.iter().enumerate() {
-        raw.push_str(quasi.value.raw.as_str());
-        if i < tpl.expressions.len() {
-            let interp = &tpl.expressions[i];
-            match interp {
-                Expression::StringLiteral(s) => raw.push_str(&s.value),
-                Expression::NumericLiteral(n) => raw.push_str(&format!("{}", n.value)),
-                _ => {
-                    // Try theme resolution
-                    if let Some(th) = theme {
-                        match eval_value_expr(interp, Some(th), filename, source) {
-                            Ok(ThemeValue::Str(s)) => { raw.push_str(&s); }
-                            Ok(ThemeValue::Num(n)) => { raw.push_str(&format!("{}", n)); }
-                            Err(e) => return Err(e),
+/// What a name bound inside a theme callback stands for.
+enum LocalBinding<'a> {
+    /// The theme, or the sub-object at this path below it
+    Theme(Vec<String>),
+    /// A `const` declared in the callback body
+    Const(&'a Expression<'a>),
+}
+
+type CallbackScope<'a> = HashMap<SymbolId, LocalBinding<'a>>;
+
+/// If `expr` is a theme callback, the expression it returns and its scope.
+fn theme_callback<'a>(
+    expr: &'a Expression<'a>,
+    scoping: &Scoping,
+) -> Option<(&'a Expression<'a>, CallbackScope<'a>)> {
+    let (params, body) = match expr.get_inner_expression() {
+        Expression::ArrowFunctionExpression(arrow) => (&arrow.params, &arrow.body),
+        Expression::FunctionExpression(func) => (&func.params, func.body.as_ref()?),
+        _ => return None,
+    };
+    if params.items.len() > 1 || params.rest.is_some() {
+        return None;
+    }
+
+    let mut scope = CallbackScope::new();
+    match params.items.first().map(|param| &param.pattern) {
+        // `(t) => …` — the parameter is the theme itself
+        Some(BindingPattern::BindingIdentifier(id)) => {
+            scope.insert(id.symbol_id(), LocalBinding::Theme(vec![]));
+        }
+        // `({ theme }) => …` — the theme is destructured from the props
+        Some(BindingPattern::ObjectPattern(props)) => {
+            for prop in &props.properties {
+                if prop.key.static_name().as_deref() == Some("theme") {
+                    bind_theme_pattern(&prop.value, vec![], &mut scope);
+                }
+            }
+        }
+        _ => {}
+    }
+
+    for stmt in &body.statements {
+        match stmt {
+            // Concise body represented as ExpressionStatement: `=> ({ ... })`
+            Statement::ExpressionStatement(es) => return Some((&es.expression, scope)),
+            // Block body with explicit return: `=> { return { ... } }`
+            Statement::ReturnStatement(ret) => return Some((ret.argument.as_ref()?, scope)),
+            Statement::VariableDeclaration(vd) if vd.kind == VariableDeclarationKind::Const => {
+                for decl in &vd.declarations {
+                    let Some(init) = decl.init.as_ref().map(Expression::get_inner_expression) else {
+                        continue;
+                    };
+                    match &decl.id {
+                        BindingPattern::BindingIdentifier(id) => {
+                            scope.insert(id.symbol_id(), LocalBinding::Const(init));
+                        }
+                        // `const { colors } = theme` destructures a theme path
+                        pattern => {
+                            if let Some(path) = theme_path(init, &scope, scoping) {
+                                bind_theme_pattern(pattern, path, &mut scope);
+                            }
                         }
-                    } else {
-                        let (line, col) = byte_offset_to_line_col(source, interp.span().start);
-                        return Err(Error::new(Status::InvalidArg, format!(
-                            "{}:{}:{}: globalCss — interpolations must be static string or number \
-                             values.\n\
-                             Hint: extract the value to a constant or use a CSS variable.",
-                            filename, line, col
-                        )));
                     }
                 }
             }
+            _ => {}
         }
     }
+    None
+}
 
-    let hash = hash_css(&raw);
-    let (css_code, css_map) = run_lightningcss(&raw, filename, dir)?;
-    Ok((hash, css_code, css_map))
+/// Bind every name in a destructuring pattern to its path below the theme.
+fn bind_theme_pattern<'a>(pattern: &'a BindingPattern<'a>, path: Vec<String>, scope: &mut CallbackScope<'a>) {
+    match pattern {
+        BindingPattern::BindingIdentifier(id) => {
+            scope.insert(id.symbol_id(), LocalBinding::Theme(path));
+        }
+        BindingPattern::ObjectPattern(obj) => {
+            for prop in &obj.properties {
+                if let Some(key) = prop.key.static_name() {
+                    let nested = path.iter().cloned().chain(std::iter::once(key.to_string())).collect();
+                    bind_theme_pattern(&prop.value, nested, scope);
+                }
+            }
+        }
+        // `{ colors = {} }` — the theme provides the value, the default is unused
+        BindingPattern::AssignmentPattern(assign) => bind_theme_pattern(&assign.left, path, scope),
+        BindingPattern::ArrayPattern(_) => {}
+    }
+}
+
+/// The theme path a member chain names, if its root is bound to the theme.
+fn theme_path(expr: &Expression, scope: &CallbackScope, scoping: &Scoping) -> Option<Vec<String>> {
+    match expr.get_inner_expression() {
+        Expression::Identifier(id) => {
+            let symbol = scoping.get_reference(id.reference_id()).symbol_id()?;
+            match scope.get(&symbol)? {
+                LocalBinding::Theme(path) => Some(path.clone()),
+                LocalBinding::Const(_) => None,
+            }
+        }
+        Expression::StaticMemberExpression(mem) => {
+            let mut path = theme_path(&mem.object, scope, scoping)?;
+            path.push(mem.property.name.to_string());
+            Some(path)
+        }
+        _ => None,
+    }
 }
 
 // ---------------------------------------------------------------------------
-// Process a keyframes tagged template literal
+// Breakpoints
+//
+// `theme.breakpoints` names the viewport widths used by shorthand keys:
+// `'@md'` is `(min-width: md)`, `'@md-down'` is `(width < md)` and
+// `'@between md lg'` is both. The same shorthand after `@container` (and an
+// optional container name) builds a container query instead. Numbers are px.
+// The plugin checks that the breakpoints increase; see plugin.js.
 // ---------------------------------------------------------------------------
 
-fn process_keyframes_template(
-    tpl: &TemplateLiteral,
-    filename: &str,
-    source: &str,
-    dir: &str,
-) -> Result<(String, String, String, Option<String>)> {
-    // Concatenate quasis and static expressions
-    let mut inner = String::new();
-    for (i, quasi) in tpl.quasis.iter().enumerate() {
-        inner.push_str(quasi.value.raw.as_str());
-        if i < tpl.expressions.len() {
-            let interp = &tpl.expressions[i];
-            match interp {
-                Expression::StringLiteral(s) => inner.push_str(&s.value),
-                Expression::NumericLiteral(n) => inner.push_str(&format!("{}", n.value)),
-                other => {
-                    let (line, col) = byte_offset_to_line_col(source, other.span().start);
-                    return Err(Error::new(Status::InvalidArg, format!(
-                        "{}:{}:{}: keyframes — interpolations must be static string or number \
-                         values.\n\
-                         Hint: extract the value to a constant.",
-                        filename, line, col
-                    )));
-                }
+/// Block at-rules without a prelude, which are never breakpoint shorthands.
+const PRELUDE_LESS_AT_RULES: &[&str] = &[
+    "font-face", "layer", "page", "position-try", "scope", "starting-style", "view-transition",
+];
+
+/// Expand a breakpoint shorthand key into its `@media` / `@container` rule.
+/// Returns `None` for any other key.
+fn breakpoint_key(key: &str, offset: u32, ctx: &EvalCtx) -> Result<Option<String>> {
+    let Some(rest) = key.strip_prefix('@') else {
+        return Ok(None);
+    };
+    if let Some(query) = rest.strip_prefix("container ") {
+        // `@container @md`, `@container sidebar @between md lg`
+        let Some(at) = query.find('@') else {
+            return Ok(None);
+        };
+        let condition = breakpoint_condition(&query[at + 1..], key, offset, ctx)?;
+        return Ok(Some(format!("@container {}{}", &query[..at], condition)));
+    }
+    let is_shorthand = rest.starts_with("between ")
+        || (!rest.contains(|c: char| c.is_whitespace() || c == '(')
+            && !PRELUDE_LESS_AT_RULES.contains(&rest));
+    if !is_shorthand {
+        return Ok(None);
+    }
+    Ok(Some(format!("@media {}", breakpoint_condition(rest, key, offset, ctx)?)))
+}
+
+/// `md` → `(min-width: 768px)`, `md-down` → `(width < 768px)`,
+/// `between md lg` → `(min-width: 768px) and (width < 1024px)`.
+fn breakpoint_condition(shorthand: &str, key: &str, offset: u32, ctx: &EvalCtx) -> Result<String> {
+    let words: Vec<&str> = shorthand.split_whitespace().collect();
+    match words.as_slice() {
+        ["between", lower, upper] => Ok(format!(
+            "(min-width: {}) and (width < {})",
+            breakpoint(lower, key, offset, ctx)?,
+            breakpoint(upper, key, offset, ctx)?
+        )),
+        [name] => match name.strip_suffix("-down") {
+            Some(base) if !breakpoint_names(ctx).contains(name) => {
+                Ok(format!("(width < {})", breakpoint(base, key, offset, ctx)?))
             }
-        }
+            _ => Ok(format!("(min-width: {})", breakpoint(name, key, offset, ctx)?)),
+        },
+        _ => Err(ctx.error_at(offset, format!(
+            "css() — '{}' is not a breakpoint shorthand.\n\
+             Hint: use '@md', '@md-down' or '@between md lg'.",
+            key
+        ))),
     }
+}
 
-    // Wrap in @keyframes with placeholder
-    let placeholder_name = "__kf_placeholder__";
-    let raw_css = format!("@keyframes {} {{ {} }}", placeholder_name, inner.trim());
+/// Breakpoint names, narrowest first. The theme arrives as JSON without its
+/// key order, so the widths are compared in px (rem and em at the root font
+/// size).
+fn breakpoint_names<'t>(ctx: &EvalCtx<'t, '_>) -> Vec<&'t str> {
+    let Some(map) = ctx.theme.and_then(|theme| theme.get("breakpoints")).and_then(serde_json::Value::as_object)
+    else {
+        return vec![];
+    };
+    let mut names: Vec<(&str, f64)> =
+        map.iter().map(|(name, value)| (name.as_str(), breakpoint_px(value))).collect();
+    names.sort_by(|a, b| a.1.total_cmp(&b.1));
+    names.into_iter().map(|(name, _)| name).collect()
+}
 
-    let hash = hash_css(&inner);
-    let kf_name = format!("kf_{}", hash);
+fn breakpoint_px(value: &serde_json::Value) -> f64 {
+    let (number, factor) = match value {
+        serde_json::Value::Number(n) => return n.as_f64().unwrap_or(f64::INFINITY),
+        serde_json::Value::String(s) => match s.trim().strip_suffix("px") {
+            Some(px) => (px, 1.0),
+            None => match s.trim().strip_suffix("rem").or_else(|| s.trim().strip_suffix("em")) {
+                Some(em) => (em, ROOT_FONT_SIZE),
+                None => return f64::INFINITY,
+            },
+        },
+        _ => return f64::INFINITY,
+    };
+    number.trim().parse::<f64>().map_or(f64::INFINITY, |n| n * factor)
+}
 
-    let (css_code, css_map) = run_lightningcss(&raw_css, filename, dir)?;
-    let final_css = css_code.replace(placeholder_name, &kf_name);
+// ---------------------------------------------------------------------------
+// Conditions
+//
+// Keys starting with `_` name a condition: `_hover: { … }` is `&:hover`,
+// `_dark` is the `[data-mode="dark"] &` selector colour schemes are scoped
+// to, `_print` is `@media print`. `theme.conditions` adds entries or
+// overrides these.
+// ---------------------------------------------------------------------------
 
-    Ok((hash, kf_name, final_css, css_map))
+/// Built-in conditions: name → selector (with `&`) or at-rule.
+const CONDITIONS: &[(&str, &str)] = &[
+    ("_hover", "&:hover"),
+    ("_focus", "&:focus"),
+    ("_focusVisible", "&:focus-visible"),
+    ("_focusWithin", "&:focus-within"),
+    ("_active", "&:active"),
+    ("_visited", "&:visited"),
+    ("_disabled", "&:disabled"),
+    ("_checked", "&:checked"),
+    ("_invalid", "&:invalid"),
+    ("_placeholder", "&::placeholder"),
+    ("_before", "&::before"),
+    ("_after", "&::after"),
+    ("_first", "&:first-child"),
+    ("_last", "&:last-child"),
+    ("_odd", "&:nth-child(odd)"),
+    ("_even", "&:nth-child(even)"),
+    ("_dark", "[data-mode=\"dark\"] &"),
+    ("_light", "[data-mode=\"light\"] &"),
+    ("_rtl", "[dir=\"rtl\"] &"),
+    ("_ltr", "[dir=\"ltr\"] &"),
+    ("_motionSafe", "@media (prefers-reduced-motion: no-preference)"),
+    ("_motionReduce", "@media (prefers-reduced-motion: reduce)"),
+    ("_print", "@media print"),
+];
+
+fn is_condition_key(key: &str) -> bool {
+    key.starts_with('_')
+}
+
+/// The selector or at-rule a condition key stands for.
+fn condition_rule<'c>(name: &str, offset: u32, ctx: &EvalCtx<'c, '_>) -> Result<&'c str> {
+    ctx.conditions.get(name).map(String::as_str).ok_or_else(|| {
+        let mut known: Vec<&str> = ctx.conditions.keys().map(String::as_str).collect();
+        known.sort_unstable();
+        ctx.error_at(offset, format!(
+            "css() — unknown condition '{}'. Known conditions: {}.\n\
+             Hint: check the spelling, or add it to theme.conditions in vite.config.js.",
+            name, known.join(", ")
+        ))
+    })
 }
 
 // ---------------------------------------------------------------------------
-// Detect whether an arrow / function expression is the `({ theme }) => ...`
-// pattern that css() uses for theming. Returns the body ObjectExpression if so.
+// Responsive and conditional values
+//
+// A property value given as an array (`padding: [8, 16, 24]`) or as an object
+// keyed by `base`, breakpoint names and conditions
+// (`color: { base: 'black', md: 'gray', _dark: 'white' }`) sets the property
+// per breakpoint and condition: the base declaration, then a min-width media
+// query per breakpoint, narrowest first, then a rule per condition. Array
+// entries after the first follow the breakpoints in order; `null` or a hole
+// skips one.
 // ---------------------------------------------------------------------------
 
-fn extract_theme_arrow_body<'a>(expr: &'a Expression<'a>) -> Option<&'a ObjectExpression<'a>> {
-    let arrow = match expr {
-        Expression::ArrowFunctionExpression(a) => a,
-        _ => return None,
-    };
+/// Where one value of a responsive or conditional property applies.
+#[derive(Clone, Copy)]
+enum Step<'t> {
+    Base,
+    Breakpoint(&'t str),
+    Condition(&'t str),
+}
 
-    // Must have exactly one parameter
-    if arrow.params.items.len() != 1 {
-        return None;
+/// Whether a property's array or object value sets it per breakpoint or
+/// condition rather than being a nested rule.
+fn is_responsive_value(value: &Expression, ctx: &EvalCtx) -> bool {
+    match value {
+        Expression::ArrayExpression(_) => true,
+        Expression::ObjectExpression(obj) => {
+            let names = breakpoint_names(ctx);
+            obj.properties.iter().any(|prop| match prop {
+                ObjectPropertyKind::ObjectProperty(p) => p.key.static_name().is_some_and(|key| {
+                    key == "base" || is_condition_key(&key) || names.contains(&key.as_ref())
+                }),
+                ObjectPropertyKind::SpreadProperty(_) => false,
+            })
+        }
+        _ => false,
     }
+}
 
-    // Walk all statements in the arrow body looking for an object expression
-    for stmt in &arrow.body.statements {
-        match stmt {
-            // Concise body represented as ExpressionStatement: `=> ({ ... })`
-            Statement::ExpressionStatement(es) => {
-                match &es.expression {
-                    Expression::ObjectExpression(obj) => return Some(obj),
-                    Expression::ParenthesizedExpression(pe) => {
-                        if let Expression::ObjectExpression(obj) = &pe.expression {
-                            return Some(obj);
-                        }
+/// The base declaration, per-breakpoint media queries and per-condition rules
+/// of a responsive or conditional value.
+fn responsive_css(key: &str, value: &Expression, indent: usize, ctx: &EvalCtx) -> Result<String> {
+    let names = breakpoint_names(ctx);
+    let mut steps: Vec<(Step, &Expression)> = vec![];
+    match value {
+        Expression::ArrayExpression(arr) => {
+            if arr.elements.len() > names.len() + 1 {
+                return Err(ctx.error_at(value.span().start, format!(
+                    "css() — '{}' has {} responsive values, but theme.breakpoints defines {}; the \
+                     first value is the base, then one per breakpoint.",
+                    key,
+                    arr.elements.len(),
+                    match names.len() {
+                        0 => "no breakpoints".to_string(),
+                        n => format!("{} ({})", n, names.join(", ")),
                     }
-                    _ => {}
-                }
+                )));
             }
-            // Block body with explicit return: `=> { return { ... } }`
-            Statement::ReturnStatement(ret) => {
-                match &ret.argument {
-                    Some(Expression::ObjectExpression(obj)) => return Some(obj),
-                    Some(Expression::ParenthesizedExpression(pe)) => {
-                        if let Expression::ObjectExpression(obj) = &pe.expression {
-                            return Some(obj);
-                        }
+            for (i, element) in arr.elements.iter().enumerate() {
+                let step = match i.checked_sub(1) {
+                    Some(i) => Step::Breakpoint(names[i]),
+                    None => Step::Base,
+                };
+                match element {
+                    ArrayExpressionElement::Elision(_) => {}
+                    ArrayExpressionElement::SpreadElement(spread) => {
+                        return Err(responsive_spread_error(key, spread.span, ctx));
                     }
-                    _ => {}
+                    element => steps.extend(element.as_expression().map(|expr| (step, expr))),
                 }
             }
-            _ => {}
+        }
+        Expression::ObjectExpression(obj) => {
+            for prop in &obj.properties {
+                let p = match prop {
+                    ObjectPropertyKind::ObjectProperty(p) => p,
+                    ObjectPropertyKind::SpreadProperty(spread) => {
+                        return Err(responsive_spread_error(key, spread.span, ctx));
+                    }
+                };
+                let name = p.key.static_name();
+                let step = match name.as_deref() {
+                    Some("base") => Step::Base,
+                    Some(name) if is_condition_key(name) => {
+                        Step::Condition(condition_rule(name, p.key.span().start, ctx)?)
+                    }
+                    name => match names.iter().find(|n| Some(**n) == name) {
+                        Some(breakpoint) => Step::Breakpoint(breakpoint),
+                        None => {
+                            return Err(ctx.error_at(p.key.span().start, format!(
+                                "css() — '{}' in the value of '{}' is neither a breakpoint in \
+                                 theme.breakpoints ({}) nor a condition.\n\
+                                 Hint: use 'base' for the value below the first breakpoint.",
+                                ctx.source_text(p.key.span()), key, names.join(", ")
+                            )));
+                        }
+                    },
+                };
+                steps.push((step, &p.value));
+            }
+            // Mobile-first: the base value, breakpoints narrowest first, then
+            // conditions in source order
+            steps.sort_by_key(|(step, _)| match step {
+                Step::Base => (0, 0),
+                Step::Breakpoint(name) => (1, names.iter().position(|n| n == name).unwrap_or(0)),
+                Step::Condition(_) => (2, 0),
+            });
+        }
+        _ => {}
+    }
+
+    let pad = "  ".repeat(indent);
+    let mut css = String::new();
+    for (step, value) in steps {
+        let rule = match step {
+            Step::Base => {
+                css.push_str(&property_css(key, value, indent, ctx)?);
+                continue;
+            }
+            Step::Breakpoint(name) => {
+                format!("@media {}", breakpoint_condition(name, key, value.span().start, ctx)?)
+            }
+            Step::Condition(rule) => rule.to_string(),
+        };
+        let declarations = property_css(key, value, indent + 1, ctx)?;
+        if !declarations.is_empty() {
+            css.push_str(&format!("{}{} {{\n{}{}}}\n", pad, rule, declarations, pad));
+        }
+    }
+    Ok(css)
+}
+
+fn responsive_spread_error(key: &str, span: Span, ctx: &EvalCtx) -> Error {
+    ctx.error_at(span.start, format!(
+        "css() — spreads are not supported in the responsive value of '{}'.\n\
+         Hint: list the value for each breakpoint.",
+        key
+    ))
+}
+
+/// The width a breakpoint names, as a CSS length.
+fn breakpoint(name: &str, key: &str, offset: u32, ctx: &EvalCtx) -> Result<String> {
+    let theme = ctx.theme.ok_or_else(|| {
+        ctx.error_at(offset, format!(
+            "css() — '{}' is a breakpoint shorthand, but no theme was provided to the plugin.\n\
+             Hint: add breakpoints to taiko({{ theme: {{ breakpoints: {{ md: 768 }} }} }}) in \
+             vite.config.js.",
+            key
+        ))
+    })?;
+    match theme.get("breakpoints").and_then(|bps| bps.get(name)) {
+        Some(serde_json::Value::String(s)) => Ok(s.clone()),
+        Some(serde_json::Value::Number(n)) => {
+            Ok(format!("{}px", format_js_number(n.as_f64().unwrap_or(0.0))))
+        }
+        Some(_) => Err(ctx.error_at(offset, format!(
+            "css() — theme.breakpoints.{} must be a length or a number of px.",
+            name
+        ))),
+        None => {
+            let names = breakpoint_names(ctx);
+            Err(ctx.error_at(offset, format!(
+                "css() — '{}' in '{}' is not a breakpoint in theme.breakpoints{}.\n\
+                 Hint: check your theme definition in vite.config.js.",
+                name,
+                key,
+                if names.is_empty() { String::new() } else { format!(" ({})", names.join(", ")) }
+            )))
         }
     }
-    None
 }
 
 // ---------------------------------------------------------------------------
 // Main NAPI export
 // ---------------------------------------------------------------------------
 
+/// Import, re-export and `export * from` specifiers of a module, excluding
+/// `taikocss/css` and type-only imports. The Vite plugin resolves these and
+/// passes the results back to `transform` as its module map.
+#[napi]
+pub fn collect_imports(filename: String, source_code: String) -> Vec<String> {
+    let allocator = Allocator::default();
+    let Some(program) = parse_module(&allocator, &filename, &source_code) else {
+        return vec![];
+    };
+
+    let mut specifiers: Vec<String> = vec![];
+    for stmt in &program.body {
+        let specifier = match stmt {
+            Statement::ImportDeclaration(import) if !import.import_kind.is_type() => &import.source,
+            Statement::ExportNamedDeclaration(en) if !en.export_kind.is_type() => match &en.source {
+                Some(source) => source,
+                None => continue,
+            },
+            Statement::ExportAllDeclaration(ea) if !ea.export_kind.is_type() => &ea.source,
+            _ => continue,
+        };
+        let specifier = specifier.value.as_str();
+        if specifier != API_MODULE && !specifiers.iter().any(|s| s == specifier) {
+            specifiers.push(specifier.to_string());
+        }
+    }
+    specifiers
+}
+
+/// `modules` maps resolved module ids to their source and resolved imports.
+/// The entry for `filename` itself supplies the transformed file's import
+/// table; without a map, imported values cannot be evaluated.
+///
+/// `defines` maps member chains (`process.env.NODE_ENV`, `import.meta.env`)
+/// to JSON values, like esbuild's `define`.
+///
+/// `helpers` registers project helper functions for style values as
+/// templates: `{ clampType: "var(--font-size-{0})" }`.
+///
+/// `targets` replaces the default browser targets with minimum versions per
+/// browser: `{ chrome: "111", safari: "16.2" }`.
 #[napi]
+#[allow(clippy::too_many_arguments)]
 pub fn transform(
     filename: String,
     source_code: String,
     theme_json: Option<String>,
     dir: Option<String>,
+    modules: Option<HashMap<String, ModuleSource>>,
+    defines: Option<HashMap<String, String>>,
+    helpers: Option<HashMap<String, String>>,
+    targets: Option<HashMap<String, String>>,
 ) -> Result<TransformResult> {
     let allocator = Allocator::default();
     let source_type = SourceType::from_path(&filename).unwrap_or_default();
@@ -830,42 +4065,133 @@ pub fn transform(
             css_rules: vec![],
             global_css: vec![],
             keyframes: vec![],
+            recipes: vec![],
             map: None,
+            dependencies: vec![],
         });
     }
 
+    // Scope analysis: resolve which callees really are the taikocss API
+    let semantic = SemanticBuilder::new().build(&program).semantic;
+
+    // Parse the pre-resolved dependencies into the same arena
+    let modules = modules.unwrap_or_default();
+    let mut dep_programs: Vec<(&str, &ModuleSource, &Program)> = vec![];
+    let mut json_modules: Vec<(&str, serde_json::Value)> = vec![];
+    for (id, module) in &modules {
+        if *id == filename {
+            continue;
+        }
+        if is_json_module(id) {
+            if let Ok(value) = serde_json::from_str(&module.code) {
+                json_modules.push((id, value));
+            }
+        } else if let Some(dep) = parse_module(&allocator, id, &module.code) {
+            dep_programs.push((id, module, allocator.alloc(dep)));
+        }
+    }
+    let dep_scopings: Vec<Scoping> = dep_programs
+        .iter()
+        .map(|(_, _, dep)| SemanticBuilder::new().build(dep).semantic.into_scoping())
+        .collect();
+
+    let no_imports = HashMap::new();
+    let root_imports = modules.get(&filename).map_or(&no_imports, |m| &m.imports);
+    let root = ModuleScope::collect(0, &filename, &source_code, &program, semantic.scoping(), root_imports);
+    let mut graph_modules = HashMap::new();
+    for (i, ((id, module, dep), scoping)) in dep_programs.iter().zip(&dep_scopings).enumerate() {
+        let scope = ModuleScope::collect(i + 1, id, &module.code, dep, scoping, &module.imports);
+        graph_modules.insert(*id, Module::Js(Box::new(scope)));
+    }
+    for (id, value) in json_modules {
+        graph_modules.insert(id, Module::Json(value));
+    }
+    let graph = ModuleGraph {
+        modules: graph_modules,
+        stack: RefCell::new(vec![]),
+        dependencies: RefCell::new(vec![]),
+        no_names: HashMap::new(),
+    };
+
     // Parse optional theme JSON
     let theme: Option<serde_json::Value> = theme_json
         .as_deref()
         .and_then(|s| serde_json::from_str(s).ok());
 
-    // Resolve text direction (default: "ltr")
-    let dir = dir.as_deref().unwrap_or("ltr");
+    // Parse build-time defines; unlike the theme, a malformed value is an error
+    let defines: HashMap<String, serde_json::Value> = defines
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(key, json)| match serde_json::from_str(&json) {
+            Ok(value) => Ok((key, value)),
+            Err(e) => Err(Error::new(Status::InvalidArg, format!(
+                "{}: defines — the value of '{}' is not valid JSON: {}",
+                filename, key, e
+            ))),
+        })
+        .collect::<Result<_>>()?;
+
+    let helpers = helpers.unwrap_or_default();
+
+    // Condition keys: the built-ins, extended or overridden by theme.conditions
+    let mut conditions: HashMap<String, String> = CONDITIONS
+        .iter()
+        .map(|(name, rule)| (name.to_string(), rule.to_string()))
+        .collect();
+    let theme_conditions = theme.as_ref().and_then(|t| t.get("conditions")).and_then(|c| c.as_object());
+    for (name, rule) in theme_conditions.into_iter().flatten() {
+        let Some(rule) = rule.as_str().filter(|_| is_condition_key(name)) else {
+            return Err(Error::new(Status::InvalidArg, format!(
+                "{}: theme.conditions — '{}' must start with '_' and map to a selector or at-rule \
+                 string, e.g. {{ _groupHover: '.group:hover &' }}.",
+                filename, name
+            )));
+        };
+        conditions.insert(name.clone(), rule.to_string());
+    }
+
+    // Resolve text direction (default: "ltr") and browser targets
+    let output = CssOutput {
+        dir: dir.as_deref().unwrap_or("ltr"),
+        targets: parse_targets(targets, &filename)?,
+    };
 
     // Replacements: (byte_start, byte_end, replacement_string)
     let mut replacements: Vec<(u32, u32, String)> = vec![];
     let mut css_rules: Vec<ExtractedCssRule> = vec![];
     let mut global_css: Vec<GlobalCssRule> = vec![];
     let mut keyframes: Vec<KeyframeRule> = vec![];
+    let mut recipes: Vec<RecipeTypes> = vec![];
 
-    // Map from JS identifier name → resolved kf_<hash> animation name.
+    // Map from binding → resolved kf_<hash> animation name.
     // Built up as we encounter keyframes`...` declarations (source order matters).
-    let mut keyframe_names: HashMap<String, String> = HashMap::new();
+    let mut keyframe_names: HashMap<SymbolId, String> = HashMap::new();
+    // Map from binding → cls_<hash> class name, for selector references
+    let mut class_names: HashMap<SymbolId, String> = HashMap::new();
 
     let mut ctx = WalkCtx {
         replacements: &mut replacements,
         css_rules: &mut css_rules,
         global_css: &mut global_css,
         keyframes: &mut keyframes,
+        recipes: &mut recipes,
         keyframe_names: &mut keyframe_names,
+        class_names: &mut class_names,
         filename: &filename,
         source: &source_code,
         theme: theme.as_ref(),
-        dir,
+        defines: &defines,
+        helpers: &helpers,
+        conditions: &conditions,
+        module: &root,
+        graph: &graph,
+        output: &output,
+        error: None,
     };
 
-    for stmt in &program.body {
-        walk_statement_ctx(stmt, &mut ctx)?;
+    ctx.visit_program(&program);
+    if let Some(e) = ctx.error {
+        return Err(e);
     }
 
     if replacements.is_empty() {
@@ -874,10 +4200,16 @@ pub fn transform(
             css_rules: vec![],
             global_css: vec![],
             keyframes: vec![],
+            recipes: vec![],
             map: None,
+            dependencies: vec![],
         });
     }
 
+    // Drop taikocss/css imports whose call sites have all been replaced
+    let import_removals = unused_api_import_replacements(&program, &semantic, &source_code, &replacements);
+    replacements.extend(import_removals);
+
     // JS source map via codegen
     let js_map: Option<String> = Codegen::new()
         .with_options(CodegenOptions {
@@ -899,253 +4231,324 @@ pub fn transform(
         );
     }
 
-    Ok(TransformResult { code: output, css_rules, global_css, keyframes, map: js_map })
+    let dependencies = graph.dependencies.take();
+    Ok(TransformResult { code: output, css_rules, global_css, keyframes, recipes, map: js_map, dependencies })
 }
 
 // ---------------------------------------------------------------------------
 // Walk context (avoids threading 8 parameters through every function)
 // ---------------------------------------------------------------------------
 
-struct WalkCtx<'b> {
+struct WalkCtx<'b, 'a> {
     replacements: &'b mut Vec<(u32, u32, String)>,
     css_rules: &'b mut Vec<ExtractedCssRule>,
     global_css: &'b mut Vec<GlobalCssRule>,
     keyframes: &'b mut Vec<KeyframeRule>,
-    keyframe_names: &'b mut HashMap<String, String>,
+    recipes: &'b mut Vec<RecipeTypes>,
+    keyframe_names: &'b mut HashMap<SymbolId, String>,
+    class_names: &'b mut HashMap<SymbolId, String>,
     filename: &'b str,
     source: &'b str,
     theme: Option<&'b serde_json::Value>,
-    dir: &'b str,
+    defines: &'b HashMap<String, serde_json::Value>,
+    helpers: &'b HashMap<String, String>,
+    conditions: &'b HashMap<String, String>,
+    /// Scope of the file being transformed
+    module: &'b ModuleScope<'b, 'a>,
+    graph: &'b ModuleGraph<'b, 'a>,
+    output: &'b CssOutput<'b>,
+    /// First extraction error. `Visit` methods cannot return a `Result`, so
+    /// the error is parked here and the rest of the traversal is skipped.
+    error: Option<Error>,
 }
 
 // ---------------------------------------------------------------------------
-// AST walkers
+// AST walker
+//
+// A full oxc `Visit` traversal, so css()/globalCss/keyframes are found in
+// every expression position (class fields, object/array literals, ternaries,
+// loops, JSX children, `module.exports = ...`). Extracted calls are not
+// descended into; everything else falls through to the default walk.
 // ---------------------------------------------------------------------------
 
-fn walk_statement_ctx<'a, 'b>(
-    stmt: &Statement<'a>,
-    ctx: &mut WalkCtx<'b>,
-) -> Result<()> {
-    match stmt {
-        Statement::ExpressionStatement(es) => {
-            walk_expression_ctx(&es.expression, ctx, None)?;
-        }
-        Statement::VariableDeclaration(vd) => {
-            for decl in &vd.declarations {
-                // Track the binding name so keyframe_names can be populated
-                let binding_name: Option<String> = decl.id.get_binding_identifier()
-                    .map(|id| id.name.to_string());
-                if let Some(init) = &decl.init {
-                    walk_expression_ctx(init, ctx, binding_name.as_deref())?;
-                }
-            }
-        }
-        Statement::ReturnStatement(rs) => {
-            if let Some(arg) = &rs.argument {
-                walk_expression_ctx(arg, ctx, None)?;
-            }
+impl<'a, 'b> Visit<'a> for WalkCtx<'b, 'a> {
+    fn visit_variable_declarator(&mut self, decl: &VariableDeclarator<'a>) {
+        if self.error.is_some() {
+            return;
         }
-        Statement::BlockStatement(bs) => {
-            for s in &bs.body {
-                walk_statement_ctx(s, ctx)?;
-            }
+        // `const fadeIn = keyframes`...`` / `const card = css({...})` — track
+        // the binding so keyframe_names and class_names can be populated
+        let binding = decl.id.get_binding_identifier();
+        let extracted = match decl.init.as_ref().map(Expression::get_inner_expression) {
+            Some(Expression::TaggedTemplateExpression(tagged)) => self.extract_tagged_template(tagged, binding),
+            Some(Expression::CallExpression(call)) => self.extract_call(call, binding),
+            _ => Ok(false),
+        };
+        match extracted {
+            Ok(true) => {}
+            Ok(false) => walk::walk_variable_declarator(self, decl),
+            Err(e) => self.error = Some(e),
         }
-        Statement::FunctionDeclaration(fd) => {
-            if let Some(body) = &fd.body {
-                for s in &body.statements {
-                    walk_statement_ctx(s, ctx)?;
-                }
-            }
+    }
+
+    fn visit_call_expression(&mut self, call: &CallExpression<'a>) {
+        if self.error.is_some() {
+            return;
         }
-        Statement::ExportNamedDeclaration(en) => {
-            if let Some(decl) = &en.declaration {
-                walk_declaration_ctx(decl, ctx)?;
-            }
+        match self.extract_call(call, None) {
+            Ok(true) => {}
+            Ok(false) => walk::walk_call_expression(self, call),
+            Err(e) => self.error = Some(e),
         }
-        Statement::ExportDefaultDeclaration(ed) => {
-            if let Some(expr) = ed.declaration.as_expression() {
-                walk_expression_ctx(expr, ctx, None)?;
-            } else if let ExportDefaultDeclarationKind::FunctionDeclaration(fd) = &ed.declaration {
-                if let Some(body) = &fd.body {
-                    for s in &body.statements {
-                        walk_statement_ctx(s, ctx)?;
-                    }
-                }
-            }
+    }
+
+    fn visit_tagged_template_expression(&mut self, tagged: &TaggedTemplateExpression<'a>) {
+        if self.error.is_some() {
+            return;
         }
-        Statement::IfStatement(is_stmt) => {
-            walk_statement_ctx(&is_stmt.consequent, ctx)?;
-            if let Some(alt) = &is_stmt.alternate {
-                walk_statement_ctx(alt, ctx)?;
-            }
+        match self.extract_tagged_template(tagged, None) {
+            Ok(true) => {}
+            Ok(false) => walk::walk_tagged_template_expression(self, tagged),
+            Err(e) => self.error = Some(e),
         }
-        _ => {}
     }
-    Ok(())
 }
 
-fn walk_declaration_ctx<'a, 'b>(
-    decl: &Declaration<'a>,
-    ctx: &mut WalkCtx<'b>,
-) -> Result<()> {
-    match decl {
-        Declaration::VariableDeclaration(vd) => {
-            for d in &vd.declarations {
-                let binding_name: Option<String> = d.id.get_binding_identifier()
-                    .map(|id| id.name.to_string());
-                if let Some(init) = &d.init {
-                    walk_expression_ctx(init, ctx, binding_name.as_deref())?;
-                }
-            }
-        }
-        Declaration::FunctionDeclaration(fd) => {
-            if let Some(body) = &fd.body {
-                for s in &body.statements {
-                    walk_statement_ctx(s, ctx)?;
-                }
-            }
+impl<'b, 'a> WalkCtx<'b, 'a> {
+    fn eval_ctx(&self) -> EvalCtx<'_, 'a> {
+        EvalCtx {
+            filename: self.filename,
+            source: self.source,
+            theme: self.theme,
+            keyframe_names: self.keyframe_names,
+            class_names: self.class_names,
+            defines: self.defines,
+            helpers: self.helpers,
+            conditions: self.conditions,
+            module: self.module,
+            graph: self.graph,
+            targets: self.output.targets,
+            locals: None,
         }
-        _ => {}
     }
-    Ok(())
-}
 
-fn walk_expression_ctx<'a, 'b>(
-    expr: &Expression<'a>,
-    ctx: &mut WalkCtx<'b>,
-    binding_name: Option<&str>,
-) -> Result<()> {
-    match expr {
-        // ── css({}) or css(({ theme }) => ({ ... })) ──────────────────────
-        Expression::CallExpression(call) => {
-            let callee_name = match &call.callee {
-                Expression::Identifier(id) => Some(id.name.as_str()),
-                _ => None,
+    /// css({}) or css(({ theme }) => ({ ... })), and the object forms of
+    /// keyframes() and globalCss(). Returns true when the call was extracted
+    /// and replaced. `binding` is the variable the class or animation name is
+    /// assigned to, if any.
+    fn extract_call(&mut self, call: &CallExpression, binding: Option<&BindingIdentifier>) -> Result<bool> {
+        let api = self.module.api.resolve(&call.callee);
+        if api == Some(ApiFn::Cx) {
+            return self.extract_cx(call, binding);
+        }
+        if api == Some(ApiFn::Recipe) {
+            return self.extract_recipe(call, binding);
+        }
+        if api == Some(ApiFn::GlobalCss) {
+            let ctx = self.eval_ctx();
+            let Some(raw) = global_css_rules(call, &ctx)? else {
+                return Err(not_static_error(call, call.arguments.first().map_or(call.span, |arg| arg.span()), &ctx));
             };
+            let (hash, css_text, css_map) = global_css_rule(&raw, self.filename, self.output)?;
+            self.replacements.push((call.span.start, call.span.end, "undefined".to_string()));
+            self.global_css.push(GlobalCssRule { hash, css: css_text, map: css_map });
+            return Ok(true);
+        }
+        if !matches!(api, Some(ApiFn::Css | ApiFn::Keyframes)) {
+            return Ok(false);
+        }
+        let ctx = self.eval_ctx();
+        let inner = match css_call_declarations(call, &ctx)? {
+            Ok(inner) => inner,
+            Err(span) => return Err(not_static_error(call, span, &ctx)),
+        };
+        if api == Some(ApiFn::Keyframes) {
+            let (hash, kf_name, css_text, css_map) = keyframes_rule(&inner, ctx.filename, self.output)?;
+            self.push_keyframes(call.span, binding, hash, kf_name, css_text, css_map);
+            return Ok(true);
+        }
+        let (class_name, css_text, css_map) = css_object_rule(&inner, ctx.filename, self.output)?;
+        self.push_css_rule(call.span, binding, class_name, css_text, css_map);
+        Ok(true)
+    }
 
-            match callee_name {
-                Some("css") => {
-                    if let Some(first_arg) = call.arguments.first() {
-                        if let Some(arg_expr) = first_arg.as_expression() {
-                            // Object form: css({ ... })
-                            if let Expression::ObjectExpression(obj) = arg_expr {
-                                match process_css_object(obj, call.span.start, ctx.filename, ctx.source, ctx.theme, ctx.keyframe_names, ctx.dir) {
-                                    Ok((class_name, css_text, css_map)) => {
-                                        ctx.replacements.push((call.span.start, call.span.end, format!("\"{}\"", class_name)));
-                                        let hash = class_name.strip_prefix("cls_").unwrap_or(&class_name).to_string();
-                                        ctx.css_rules.push(ExtractedCssRule { hash, css: css_text, map: css_map });
-                                        return Ok(());
-                                    }
-                                    Err(e) => return Err(e),
-                                }
-                            }
-
-                            // Function form: css(({ theme }) => ({ ... }))
-                            if let Some(body_obj) = extract_theme_arrow_body(arg_expr) {
-                                match process_css_object(body_obj, call.span.start, ctx.filename, ctx.source, ctx.theme, ctx.keyframe_names, ctx.dir) {
-                                    Ok((class_name, css_text, css_map)) => {
-                                        ctx.replacements.push((call.span.start, call.span.end, format!("\"{}\"", class_name)));
-                                        let hash = class_name.strip_prefix("cls_").unwrap_or(&class_name).to_string();
-                                        ctx.css_rules.push(ExtractedCssRule { hash, css: css_text, map: css_map });
-                                        return Ok(());
-                                    }
-                                    Err(e) => return Err(e),
-                                }
-                            }
-                        }
-                    }
+    /// cx(button, isPrimary && primary) whose arguments are all static css()
+    /// classes or styles: one class with their styles deep-merged in order.
+    /// Anything else — a runtime condition, a class name string — is left to
+    /// the runtime cx(), which joins the class names.
+    ///
+    /// The css() classes among the arguments stay on the element, so selectors
+    /// that reference them (`${card}:hover &`) still match; the merged class
+    /// sits one tier above them so argument order still decides conflicts.
+    fn extract_cx(&mut self, call: &CallExpression, binding: Option<&BindingIdentifier>) -> Result<bool> {
+        let ctx = self.eval_ctx();
+        let dynamic = call.arguments.iter().any(|arg| arg.as_expression().is_some_and(|style| depends_on_runtime(style, &ctx)));
+        if dynamic {
+            return Ok(false);
+        }
+        let entries = match css_call_entries(call, &ctx)? {
+            Ok(entries) => entries,
+            Err(_) => return Ok(false),
+        };
+        let mut classes = vec![];
+        for style in call.arguments.iter().filter_map(Argument::as_expression) {
+            cx_classes(style, &ctx, &mut classes)?;
+        }
+        let tier = if classes.is_empty() { 1 } else { 2 };
+        let (class_name, css_text, css_map) = css_rule_at_tier(&entries.into_css(), tier, self.filename, self.output)?;
+        classes.push(class_name.clone());
+        self.replacements.push((call.span.start, call.span.end, format!("\"{}\"", classes.join(" "))));
+        self.push_class_rule(binding, class_name, css_text, css_map);
+        Ok(true)
+    }
 
-                    // Recurse into callee and args for nested calls
-                    walk_expression_ctx(&call.callee, ctx, None)?;
-                    for arg in &call.arguments {
-                        if let Some(e) = arg.as_expression() {
-                            walk_expression_ctx(e, ctx, None)?;
-                        }
-                    }
-                }
+    /// recipe({ ... }): one class per style, and the call replaced with a
+    /// selector function over those classes.
+    fn extract_recipe(&mut self, call: &CallExpression, binding: Option<&BindingIdentifier>) -> Result<bool> {
+        let parts = recipe_parts(call, &self.eval_ctx())?;
 
-                // (Other call expressions — recurse)
-                _ => {
-                    walk_expression_ctx(&call.callee, ctx, None)?;
-                    for arg in &call.arguments {
-                        if let Some(e) = arg.as_expression() {
-                            walk_expression_ctx(e, ctx, None)?;
-                        }
-                    }
+        let base = match &parts.base {
+            Some(declarations) => self.recipe_class(declarations, 1)?,
+            None => None,
+        };
+        let mut variants = serde_json::Map::new();
+        for (group, values) in &parts.variants {
+            let mut classes = serde_json::Map::new();
+            for (value, declarations) in values {
+                if let Some(class_name) = self.recipe_class(declarations, 2)? {
+                    classes.insert(value.clone(), class_name.into());
                 }
             }
+            variants.insert(group.clone(), classes.into());
         }
-
-        // ── globalCss`...` tagged template ────────────────────────────────
-        Expression::TaggedTemplateExpression(tagged) => {
-            let is_global_css = matches!(
-                &tagged.tag,
-                Expression::Identifier(id) if id.name.as_str() == "globalCss"
-            );
-            let is_keyframes = matches!(
-                &tagged.tag,
-                Expression::Identifier(id) if id.name.as_str() == "keyframes"
-            );
-
-            if is_global_css {
-                match process_global_css_template(&tagged.quasi, ctx.filename, ctx.source, ctx.theme, ctx.dir) {
-                    Ok((hash, css_text, css_map)) => {
-                        // Replace the call expression with `undefined` (side-effect: the import
-                        // is prepended in the Vite plugin)
-                        ctx.replacements.push((tagged.span.start, tagged.span.end, "undefined".to_string()));
-                        ctx.global_css.push(GlobalCssRule { hash, css: css_text, map: css_map });
-                        return Ok(());
-                    }
-                    Err(e) => return Err(e),
-                }
+        let mut compounds = vec![];
+        for compound in &parts.compounds {
+            if let Some(class_name) = self.recipe_class(&compound.declarations, 3)? {
+                let conditions: serde_json::Map<String, serde_json::Value> = compound
+                    .conditions
+                    .iter()
+                    .map(|(group, values, _)| (group.clone(), values.clone().into()))
+                    .collect();
+                compounds.push(serde_json::json!([conditions, class_name]));
             }
+        }
+        let defaults: serde_json::Map<String, serde_json::Value> = parts
+            .defaults
+            .iter()
+            .map(|(group, value, _)| (group.clone(), value.clone().into()))
+            .collect();
 
-            if is_keyframes {
-                match process_keyframes_template(&tagged.quasi, ctx.filename, ctx.source, ctx.dir) {
-                    Ok((hash, kf_name, css_text, css_map)) => {
-                        ctx.replacements.push((tagged.span.start, tagged.span.end, format!("\"{}\"", kf_name)));
-                        // Register the binding name → kf_name for later css() interpolation
-                        if let Some(name) = binding_name {
-                            ctx.keyframe_names.insert(name.to_string(), kf_name.clone());
-                        }
-                        ctx.keyframes.push(KeyframeRule { hash, name: kf_name, css: css_text, map: css_map });
-                        return Ok(());
-                    }
-                    Err(e) => return Err(e),
-                }
-            }
+        // Props fall back to the defaults; values compare as strings, so
+        // `disabled: true` selects the `true` variant
+        let selector = format!(
+            "/*#__PURE__*/((v, d, c, b) => (p = {{}}) => {{ const r = [b]; \
+             for (const k in v) r.push(v[k][p[k] ?? d[k]]); \
+             for (const [w, x] of c) if (Object.keys(w).every((k) => w[k].includes(String(p[k] ?? d[k])))) r.push(x); \
+             return r.filter(Boolean).join(\" \") }})({}, {}, {}, {})",
+            serde_json::Value::Object(variants),
+            serde_json::Value::Object(defaults),
+            serde_json::Value::Array(compounds),
+            serde_json::json!(base),
+        );
+        self.replacements.push((call.span.start, call.span.end, selector));
+        self.recipes.push(RecipeTypes {
+            name: binding.map(|id| id.name.to_string()),
+            types: recipe_types(binding.map(|id| id.name.as_str()), &parts),
+        });
+        Ok(true)
+    }
+
+    /// The class of one recipe style; `None` when it has no declarations.
+    /// Base, variant and compound styles sit at tiers 1, 2 and 3, so each
+    /// overrides the one before by specificity rather than by stylesheet order.
+    fn recipe_class(&mut self, declarations: &str, tier: usize) -> Result<Option<String>> {
+        if declarations.trim().is_empty() {
+            return Ok(None);
         }
+        let (class_name, css, map) = css_rule_at_tier(declarations, tier, self.filename, self.output)?;
+        let hash = class_name.strip_prefix("cls_").unwrap_or(&class_name).to_string();
+        self.css_rules.push(ExtractedCssRule { hash, css, map });
+        Ok(Some(class_name))
+    }
 
-        Expression::ArrowFunctionExpression(arrow) => {
-            for s in &arrow.body.statements {
-                walk_statement_ctx(s, ctx)?;
-            }
+    /// Replace a css() call or template with its class name and register the
+    /// binding for later selector references.
+    fn push_css_rule(
+        &mut self,
+        span: Span,
+        binding: Option<&BindingIdentifier>,
+        class_name: String,
+        css: String,
+        map: Option<String>,
+    ) {
+        self.replacements.push((span.start, span.end, format!("\"{}\"", class_name)));
+        self.push_class_rule(binding, class_name, css, map);
+    }
+
+    /// Register an extracted class rule and the binding it is assigned to.
+    fn push_class_rule(
+        &mut self,
+        binding: Option<&BindingIdentifier>,
+        class_name: String,
+        css: String,
+        map: Option<String>,
+    ) {
+        if let Some(id) = binding {
+            self.class_names.insert(id.symbol_id(), class_name.clone());
         }
+        let hash = class_name.strip_prefix("cls_").unwrap_or(&class_name).to_string();
+        self.css_rules.push(ExtractedCssRule { hash, css, map });
+    }
 
-        Expression::JSXElement(el) => {
-            for attr in &el.opening_element.attributes {
-                if let JSXAttributeItem::Attribute(a) = attr {
-                    if let Some(JSXAttributeValue::ExpressionContainer(ec)) = &a.value {
-                        if let Some(e) = ec.expression.as_expression() {
-                            walk_expression_ctx(e, ctx, None)?;
-                        }
-                    }
-                }
+    /// css`...` / globalCss`...` / keyframes`...` tagged templates.
+    /// `binding` is the variable a css or keyframes result is assigned to, if
+    /// any.
+    fn extract_tagged_template(
+        &mut self,
+        tagged: &TaggedTemplateExpression,
+        binding: Option<&BindingIdentifier>,
+    ) -> Result<bool> {
+        match self.module.api.resolve(&tagged.tag) {
+            Some(ApiFn::Css) => {
+                let inner = tagged_template_body(&tagged.quasi, &self.eval_ctx())?;
+                let (class_name, css_text, css_map) = css_object_rule(&inner, self.filename, self.output)?;
+                self.push_css_rule(tagged.span, binding, class_name, css_text, css_map);
+                Ok(true)
             }
-            for child in &el.children {
-                if let JSXChild::ExpressionContainer(ec) = child {
-                    if let Some(e) = ec.expression.as_expression() {
-                        walk_expression_ctx(e, ctx, None)?;
-                    }
-                }
+            Some(ApiFn::GlobalCss) => {
+                let (hash, css_text, css_map) =
+                    process_global_css_template(&tagged.quasi, &self.eval_ctx(), self.output)?;
+                // Replace the call expression with `undefined` (side-effect: the import
+                // is prepended in the Vite plugin)
+                self.replacements.push((tagged.span.start, tagged.span.end, "undefined".to_string()));
+                self.global_css.push(GlobalCssRule { hash, css: css_text, map: css_map });
+                Ok(true)
             }
+            Some(ApiFn::Keyframes) => {
+                let inner = tagged_template_body(&tagged.quasi, &self.eval_ctx())?;
+                let (hash, kf_name, css_text, css_map) = keyframes_rule(&inner, self.filename, self.output)?;
+                self.push_keyframes(tagged.span, binding, hash, kf_name, css_text, css_map);
+                Ok(true)
+            }
+            _ => Ok(false),
         }
+    }
 
-        Expression::ParenthesizedExpression(pe) => {
-            walk_expression_ctx(&pe.expression, ctx, None)?;
+    /// Replace a keyframes template or call with its animation name and
+    /// register the binding for later css() references.
+    fn push_keyframes(
+        &mut self,
+        span: Span,
+        binding: Option<&BindingIdentifier>,
+        hash: String,
+        kf_name: String,
+        css: String,
+        map: Option<String>,
+    ) {
+        self.replacements.push((span.start, span.end, format!("\"{}\"", kf_name)));
+        if let Some(id) = binding {
+            self.keyframe_names.insert(id.symbol_id(), kf_name.clone());
         }
-
-        _ => {}
+        self.keyframes.push(KeyframeRule { hash, name: kf_name, css, map });
     }
-    Ok(())
-}
\ No newline at end of file
+}
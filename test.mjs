@@ -2,7 +2,7 @@ import { createRequire } from 'module'
 const require = createRequire(import.meta.url)
 // index.js is a CJS file generated by NAPI-RS. Because package.json sets
 // "type":"module", Node would parse .js as ESM. loader.cjs forces CJS context.
-const { transform } = require('./loader.cjs')
+const { transform, collectImports } = require('./loader.cjs')
 
 // ─── helpers ────────────────────────────────────────────────────────────────
 
@@ -57,36 +57,36 @@ function assertThrows(fn, match, label) {
 console.log('\n── Basic extraction ───────────────────────────────────────────')
 
 test('replaces css({}) call with a string literal', () => {
-  const { code } = transform('test.jsx', `const btn = css({ color: 'red' })`)
+  const { code } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst btn = css({ color: 'red' })`)
   assert(code.includes('"cls_'), `expected cls_ class name, got: ${code}`)
   assert(!code.includes('css({'), `css() call should be gone, got: ${code}`)
 })
 
 test('returns a css rule', () => {
-  const { cssRules } = transform('test.jsx', `const btn = css({ color: 'red' })`)
+  const { cssRules } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst btn = css({ color: 'red' })`)
   assert(cssRules.length === 1, `expected 1 rule, got ${cssRules.length}`)
   assert(cssRules[0].css.includes('red'), `expected color:red in css, got: ${cssRules[0].css}`)
   assert(cssRules[0].hash.length > 0, 'hash should be non-empty')
 })
 
 test('camelCase → kebab-case', () => {
-  const { cssRules } = transform('test.jsx', `const x = css({ backgroundColor: 'blue' })`)
+  const { cssRules } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ backgroundColor: 'blue' })`)
   assert(cssRules[0].css.includes('background-color'), `got: ${cssRules[0].css}`)
 })
 
 test('numeric value gets px suffix', () => {
-  const { cssRules } = transform('test.jsx', `const x = css({ padding: 16 })`)
+  const { cssRules } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ padding: 16 })`)
   assert(cssRules[0].css.includes('16px'), `got: ${cssRules[0].css}`)
 })
 
 test('unitless numeric (opacity) gets no px', () => {
-  const { cssRules } = transform('test.jsx', `const x = css({ opacity: 0.5 })`)
+  const { cssRules } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ opacity: 0.5 })`)
   assert(!cssRules[0].css.includes('px'), `got: ${cssRules[0].css}`)
   assert(cssRules[0].css.includes('.5'), `got: ${cssRules[0].css}`)
 })
 
 test('unitless numeric (fontWeight) gets no px', () => {
-  const { cssRules } = transform('test.jsx', `const x = css({ fontWeight: 700 })`)
+  const { cssRules } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ fontWeight: 700 })`)
   assert(!cssRules[0].css.includes('px'), `got: ${cssRules[0].css}`)
 })
 
@@ -94,6 +94,7 @@ console.log('\n── Nesting & media queries ───────────
 
 test('&:hover nesting', () => {
   const { cssRules } = transform('test.jsx', `
+    import { css } from 'taikocss/css'
     const x = css({ color: 'red', '&:hover': { color: 'blue' } })
   `)
   assert(cssRules.length >= 1, 'expected at least 1 rule')
@@ -103,6 +104,7 @@ test('&:hover nesting', () => {
 
 test('@media query', () => {
   const { cssRules } = transform('test.jsx', `
+    import { css } from 'taikocss/css'
     const x = css({ padding: '8px', '@media (max-width: 600px)': { padding: '4px' } })
   `)
   const allCss = cssRules.map(r => r.css).join('')
@@ -113,7 +115,7 @@ console.log('\n── Full component (spec example) ─────────
 
 test('full Button component', () => {
   const source = `
-    import { css } from './css'
+    import { css } from 'taikocss/css'
     const button = css({
       backgroundColor: 'oklch(60% 0.2 250)',
       padding: '8px 16px',
@@ -148,6 +150,7 @@ test('no css() call → returns source unchanged', () => {
 
 test('multiple css() calls in one file', () => {
   const { code, cssRules } = transform('test.jsx', `
+    import { css } from 'taikocss/css'
     const a = css({ color: 'red' })
     const b = css({ color: 'blue' })
   `)
@@ -167,6 +170,7 @@ console.log('\n── Function body, default export, error cases ─────
 
 test('css() inside a function body — extracted correctly', () => {
   const src = `
+    import { css } from 'taikocss/css'
     function makeStyles() {
       const card = css({ padding: '16px', borderRadius: 8 })
       return card
@@ -179,7 +183,7 @@ test('css() inside a function body — extracted correctly', () => {
 })
 
 test('css() as a default export — extracted correctly', () => {
-  const src = `export default css({ display: 'flex', alignItems: 'center' })`
+  const src = `import { css } from 'taikocss/css'\nexport default css({ display: 'flex', alignItems: 'center' })`
   const { code, cssRules } = transform('test.jsx', src)
   assert(cssRules.length === 1, `expected 1 rule, got ${cssRules.length}`)
   assert(cssRules[0].css.includes('flex'), `expected flex in css, got: ${cssRules[0].css}`)
@@ -189,7 +193,8 @@ test('css() as a default export — extracted correctly', () => {
 
 test('dynamic value → build error with file/line info', () => {
   const src = `
-    const myColor = 'red'
+    import { css } from 'taikocss/css'
+    const myColor = pickColor()
     const x = css({ color: myColor })
   `
   assertThrows(
@@ -200,7 +205,7 @@ test('dynamic value → build error with file/line info', () => {
 })
 
 test('dynamic value error message mentions the offending identifier or property', () => {
-  const src = `const x = css({ backgroundColor: someVar })`
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ backgroundColor: someVar })`
   assertThrows(
     () => transform('test.tsx', src),
     // Error should mention either the property name or the dynamic identifier
@@ -210,7 +215,7 @@ test('dynamic value error message mentions the offending identifier or property'
 })
 
 test('dynamic value error message includes hint', () => {
-  const src = `const x = css({ color: someVar })`
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ color: someVar })`
   assertThrows(
     () => transform('test.tsx', src),
     'Hint:',
@@ -218,9 +223,10 @@ test('dynamic value error message includes hint', () => {
   )
 })
 
-test('spread in object → build error', () => {
+test('spread of a runtime value → build error', () => {
   const src = `
-    const base = { color: 'red' }
+    import { css } from 'taikocss/css'
+    const base = makeStyles()
     const x = css({ ...base, padding: '8px' })
   `
   assertThrows(
@@ -231,7 +237,7 @@ test('spread in object → build error', () => {
 })
 
 test('spread error includes file/line info', () => {
-  const src = `const x = css({ ...base })`
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ ...base })`
   assertThrows(
     () => transform('src/Component.tsx', src),
     /src\/Component\.tsx:\d+:\d+/,
@@ -244,7 +250,7 @@ console.log('\n── TSX, deduplication, numerics, vendor prefixes ────
 test('TSX file with type annotations — extracted correctly, types stripped', () => {
   const src = `
     import React from 'react'
-    import { css } from './css'
+    import { css } from 'taikocss/css'
     const styles = css({ color: 'green', fontSize: 14 })
     const MyComp: React.FC<{ label: string }> = ({ label }) => (
       <div className={styles}>{label}</div>
@@ -260,8 +266,8 @@ test('TSX file with type annotations — extracted correctly, types stripped', (
 })
 
 test('two identical css({}) objects produce the same hash', () => {
-  const src1 = `const a = css({ color: 'red', padding: '8px' })`
-  const src2 = `const b = css({ color: 'red', padding: '8px' })`
+  const src1 = `import { css } from 'taikocss/css'\nconst a = css({ color: 'red', padding: '8px' })`
+  const src2 = `import { css } from 'taikocss/css'\nconst b = css({ color: 'red', padding: '8px' })`
   const r1 = transform('fileA.jsx', src1)
   const r2 = transform('fileB.jsx', src2)
   assert(r1.cssRules.length === 1 && r2.cssRules.length === 1, 'each file should produce 1 rule')
@@ -272,8 +278,8 @@ test('two identical css({}) objects produce the same hash', () => {
 })
 
 test('two different css({}) objects produce different hashes', () => {
-  const src1 = `const a = css({ color: 'red' })`
-  const src2 = `const b = css({ color: 'blue' })`
+  const src1 = `import { css } from 'taikocss/css'\nconst a = css({ color: 'red' })`
+  const src2 = `import { css } from 'taikocss/css'\nconst b = css({ color: 'blue' })`
   const r1 = transform('fileA.jsx', src1)
   const r2 = transform('fileB.jsx', src2)
   assert(
@@ -283,23 +289,23 @@ test('two different css({}) objects produce different hashes', () => {
 })
 
 test('integer numeric → Npx (no decimal point)', () => {
-  const { cssRules } = transform('test.jsx', `const x = css({ marginTop: 16 })`)
+  const { cssRules } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ marginTop: 16 })`)
   assert(cssRules[0].css.includes('16px'), `got: ${cssRules[0].css}`)
   assert(!cssRules[0].css.includes('16.'), `should not have decimal, got: ${cssRules[0].css}`)
 })
 
 test('float numeric → N.Npx', () => {
-  const { cssRules } = transform('test.jsx', `const x = css({ letterSpacing: 1.5 })`)
+  const { cssRules } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ letterSpacing: 1.5 })`)
   assert(cssRules[0].css.includes('1.5px'), `got: ${cssRules[0].css}`)
 })
 
 test('zero value → 0 (LightningCSS drops the px unit)', () => {
-  const { cssRules } = transform('test.jsx', `const x = css({ margin: 0 })`)
+  const { cssRules } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ margin: 0 })`)
   assert(cssRules[0].css.includes('margin:0') || cssRules[0].css.includes('margin: 0'), `got: ${cssRules[0].css}`)
 })
 
 test('vendor-prefix property (WebkitAppearance) passes through LightningCSS', () => {
-  const { cssRules } = transform('test.jsx', `const x = css({ WebkitAppearance: 'none' })`)
+  const { cssRules } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ WebkitAppearance: 'none' })`)
   const css = cssRules[0].css
   assert(
     css.includes('appearance') || css.includes('-webkit-appearance'),
@@ -310,19 +316,19 @@ test('vendor-prefix property (WebkitAppearance) passes through LightningCSS', ()
 console.log('\n── Source map fields ──────────────────────────────────────────')
 
 test('transform result has a map field', () => {
-  const { map } = transform('test.jsx', `const x = css({ color: 'red' })`)
+  const { map } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ color: 'red' })`)
   assert(map === null || typeof map === 'string', `map should be null or string, got: ${typeof map}`)
 })
 
 test('css rule has a map field', () => {
-  const { cssRules } = transform('test.jsx', `const x = css({ color: 'red' })`)
+  const { cssRules } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ color: 'red' })`)
   assert(cssRules.length === 1, 'expected 1 rule')
   const { map } = cssRules[0]
   assert(map === null || typeof map === 'string', `rule.map should be null or string, got: ${typeof map}`)
 })
 
 test('css rule map is valid JSON when present', () => {
-  const { cssRules } = transform('test.jsx', `const x = css({ color: 'red' })`)
+  const { cssRules } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ color: 'red' })`)
   const { map } = cssRules[0]
   if (map !== null) {
     let parsed
@@ -349,7 +355,7 @@ const THEME = JSON.stringify({
 console.log('\n── v3: Theming ────────────────────────────────────────────────')
 
 test('css(({ theme }) => ({ color: theme.colors.primary })) — resolved to "tomato"', () => {
-  const src = `const x = css(({ theme }) => ({ color: theme.colors.primary }))`
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ color: theme.colors.primary }))`
   const { code, cssRules } = transform('test.jsx', src, THEME)
   assert(cssRules.length === 1, `expected 1 rule, got ${cssRules.length}`)
   assert(cssRules[0].css.includes('tomato'), `expected tomato in css, got: ${cssRules[0].css}`)
@@ -358,14 +364,14 @@ test('css(({ theme }) => ({ color: theme.colors.primary })) — resolved to "tom
 })
 
 test('css(({ theme }) => ({ fontSize: theme.spacing.unit * 4 })) — 32 → 32px', () => {
-  const src = `const x = css(({ theme }) => ({ fontSize: theme.spacing.unit * 4 }))`
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ fontSize: theme.spacing.unit * 4 }))`
   const { cssRules } = transform('test.jsx', src, THEME)
   assert(cssRules.length === 1, `expected 1 rule, got ${cssRules.length}`)
   assert(cssRules[0].css.includes('32px'), `expected 32px, got: ${cssRules[0].css}`)
 })
 
 test('theme member that does not exist → build error', () => {
-  const src = `const x = css(({ theme }) => ({ color: theme.colors.nonexistent }))`
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ color: theme.colors.nonexistent }))`
   assertThrows(
     () => transform('test.jsx', src, THEME),
     'nonexistent',
@@ -374,7 +380,7 @@ test('theme member that does not exist → build error', () => {
 })
 
 test('computed theme member (theme.colors[key]) → build error', () => {
-  const src = `const x = css(({ theme }) => ({ color: theme.colors[dynamicKey] }))`
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ color: theme.colors[dynamicKey] }))`
   assertThrows(
     () => transform('test.jsx', src, THEME),
     /computed/i,
@@ -383,21 +389,21 @@ test('computed theme member (theme.colors[key]) → build error', () => {
 })
 
 test('theme string concatenation with + operator', () => {
-  const src = `const x = css(({ theme }) => ({ color: theme.colors.primary + ' !important' }))`
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ color: theme.colors.primary + ' !important' }))`
   const { cssRules } = transform('test.jsx', src, THEME)
   assert(cssRules.length === 1, `expected 1 rule, got ${cssRules.length}`)
   assert(cssRules[0].css.includes('tomato'), `expected tomato in css, got: ${cssRules[0].css}`)
 })
 
 test('theme addition of two numbers', () => {
-  const src = `const x = css(({ theme }) => ({ padding: theme.spacing.unit + 2 }))`
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ padding: theme.spacing.unit + 2 }))`
   const { cssRules } = transform('test.jsx', src, THEME)
   assert(cssRules.length === 1, `expected 1 rule, got ${cssRules.length}`)
   assert(cssRules[0].css.includes('10px'), `expected 10px (8+2), got: ${cssRules[0].css}`)
 })
 
 test('theme without taiko — theme reference errors clearly', () => {
-  const src = `const x = css(({ theme }) => ({ color: theme.colors.primary }))`
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ color: theme.colors.primary }))`
   assertThrows(
     () => transform('test.jsx', src, null),
     'theme',
@@ -408,7 +414,7 @@ test('theme without taiko — theme reference errors clearly', () => {
 console.log('\n── v3: globalCss tagged template ──────────────────────────────')
 
 test('globalCss`body { margin: 0 }` — virtual global CSS module emitted', () => {
-  const src = "globalCss`body { margin: 0; }`"
+  const src = "import { globalCss } from 'taikocss/css'\nglobalCss`body { margin: 0; }`"
   const { code, globalCss } = transform('test.jsx', src)
   assert(globalCss.length === 1, `expected 1 globalCss rule, got ${globalCss.length}`)
   assert(globalCss[0].css.includes('margin'), `expected margin in css, got: ${globalCss[0].css}`)
@@ -418,22 +424,22 @@ test('globalCss`body { margin: 0 }` — virtual global CSS module emitted', () =
 })
 
 test('globalCss with static interpolation — correctly concatenated', () => {
-  const src = "const size = '16px'; globalCss`body { font-size: ${16}px; }`"
+  const src = "import { globalCss } from 'taikocss/css'\nconst size = '16px'; globalCss`body { font-size: ${16}px; }`"
   const { globalCss } = transform('test.jsx', src)
   // The interpolation 16 is a numeric literal — should be concatenated
   assert(globalCss.length === 1, `expected 1 globalCss rule, got ${globalCss.length}`)
 })
 
 test('two identical globalCss bodies → same hash', () => {
-  const r1 = transform('a.jsx', "globalCss`body { margin: 0; }`")
-  const r2 = transform('b.jsx', "globalCss`body { margin: 0; }`")
+  const r1 = transform('a.jsx', "import { globalCss } from 'taikocss/css'\nglobalCss`body { margin: 0; }`")
+  const r2 = transform('b.jsx', "import { globalCss } from 'taikocss/css'\nglobalCss`body { margin: 0; }`")
   assert(r1.globalCss[0].hash === r2.globalCss[0].hash, 'same body → same hash')
 })
 
 console.log('\n── v3: keyframes tagged template ──────────────────────────────')
 
 test('keyframes`from{opacity:0}to{opacity:1}` — virtual module, name returned', () => {
-  const src = "const fadeIn = keyframes`from { opacity: 0; } to { opacity: 1; }`"
+  const src = "import { keyframes } from 'taikocss/css'\nconst fadeIn = keyframes`from { opacity: 0; } to { opacity: 1; }`"
   const { code, keyframes } = transform('test.jsx', src)
   assert(keyframes.length === 1, `expected 1 keyframe rule, got ${keyframes.length}`)
   assert(keyframes[0].name.startsWith('kf_'), `expected kf_ prefix, got: ${keyframes[0].name}`)
@@ -445,6 +451,7 @@ test('keyframes`from{opacity:0}to{opacity:1}` — virtual module, name returned'
 
 test('keyframes name interpolated into css() — correctly resolved', () => {
   const src = `
+    import { css, keyframes } from 'taikocss/css'
     const fadeIn = keyframes\`from { opacity: 0; } to { opacity: 1; }\`
     const box = css({ animation: \`\${fadeIn} 0.5s ease-out\` })
   `
@@ -457,15 +464,15 @@ test('keyframes name interpolated into css() — correctly resolved', () => {
 })
 
 test('two identical keyframes bodies → same hash, single virtual module', () => {
-  const r1 = transform('a.jsx', "const a = keyframes`from { opacity: 0; } to { opacity: 1; }`")
-  const r2 = transform('b.jsx', "const b = keyframes`from { opacity: 0; } to { opacity: 1; }`")
+  const r1 = transform('a.jsx', "import { keyframes } from 'taikocss/css'\nconst a = keyframes`from { opacity: 0; } to { opacity: 1; }`")
+  const r2 = transform('b.jsx', "import { keyframes } from 'taikocss/css'\nconst b = keyframes`from { opacity: 0; } to { opacity: 1; }`")
   assert(r1.keyframes[0].hash === r2.keyframes[0].hash, 'same body → same hash')
 })
 
 console.log('\n── v3: container queries ──────────────────────────────────────')
 
 test('@container rule in css() object — passed through and minified', () => {
-  const src = `const x = css({
+  const src = `import { css, container } from 'taikocss/css'\nconst x = css({
     fontSize: '2rem',
     '@container (max-width: 768px)': { fontSize: '1.5rem' },
   })`
@@ -475,7 +482,7 @@ test('@container rule in css() object — passed through and minified', () => {
 })
 
 test('named container query — passed through correctly', () => {
-  const src = `const x = css({
+  const src = `import { css } from 'taikocss/css'\nconst x = css({
     '@container sidebar (max-width: 300px)': { display: 'none' },
   })`
   const { cssRules } = transform('test.jsx', src)
@@ -484,7 +491,7 @@ test('named container query — passed through correctly', () => {
 })
 
 test('container() spread — expanded to container-type / container-name properties', () => {
-  const src = `const sidebar = css({ ...container('sidebar', 'inline-size'), width: '250px' })`
+  const src = `import { css, container } from 'taikocss/css'\nconst sidebar = css({ ...container('sidebar', 'inline-size'), width: '250px' })`
   const { cssRules } = transform('test.jsx', src)
   assert(cssRules.length === 1, `expected 1 rule, got ${cssRules.length}`)
   const css = cssRules[0].css
@@ -500,7 +507,7 @@ test('container() spread — expanded to container-type / container-name propert
 })
 
 test('container() with type only — emits container-type without container-name', () => {
-  const src = `const x = css({ ...container('inline-size') })`
+  const src = `import { css, container } from 'taikocss/css'\nconst x = css({ ...container('inline-size') })`
   const { cssRules } = transform('test.jsx', src)
   const css = cssRules[0].css
   assert(css.includes('container-type'), `expected container-type in css, got: ${css}`)
@@ -510,107 +517,2456 @@ test('container() with type only — emits container-type without container-name
 console.log('\n── v3: RTL direction ──────────────────────────────────────────')
 
 test('dir option accepted — ltr produces css rule', () => {
-  const { cssRules } = transform('test.jsx', `const x = css({ paddingLeft: '8px' })`, null, 'ltr')
+  const { cssRules } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ paddingLeft: '8px' })`, null, 'ltr')
   assert(cssRules.length === 1, `expected 1 rule, got ${cssRules.length}`)
   assert(cssRules[0].css.length > 0, 'expected non-empty css')
 })
 
 test('dir option accepted — rtl produces css rule', () => {
-  const { cssRules } = transform('test.jsx', `const x = css({ paddingLeft: '8px' })`, null, 'rtl')
+  const { cssRules } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ paddingLeft: '8px' })`, null, 'rtl')
   assert(cssRules.length === 1, `expected 1 rule, got ${cssRules.length}`)
   assert(cssRules[0].css.length > 0, 'expected non-empty css')
 })
 
 test('no dir option defaults to ltr', () => {
-  const r1 = transform('test.jsx', `const x = css({ color: 'red' })`)
-  const r2 = transform('test.jsx', `const x = css({ color: 'red' })`, null, 'ltr')
+  const r1 = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ color: 'red' })`)
+  const r2 = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ color: 'red' })`, null, 'ltr')
   assert(r1.cssRules[0].css === r2.cssRules[0].css, 'default should match explicit ltr')
 })
 
-// ─── v4: Distribution layer ──────────────────────────────────────────────────
+console.log('\n── Module-level constants ─────────────────────────────────────')
 
-console.log('\n── v4: loader ─────────────────────────────────────────────────')
+test('literal const — const gutter = 12 → 12px', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const gutter = 12
+    const x = css({ padding: gutter })
+  `
+  const { cssRules } = transform('test.jsx', src)
+  assert(cssRules[0].css.includes('padding:12px'), `expected padding:12px, got: ${cssRules[0].css}`)
+})
 
-test('loader.cjs loads without error and exposes transform', () => {
-  const binding = require('./loader.cjs')
-  assert(typeof binding.transform === 'function', `transform should be a function, got: ${typeof binding.transform}`)
+test('exported const string is folded', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    export const brand = 'rebeccapurple'
+    const x = css({ color: brand })
+  `
+  const { cssRules } = transform('test.jsx', src)
+  assert(cssRules[0].css.includes('#639'), `expected rebeccapurple in css, got: ${cssRules[0].css}`)
 })
 
-test('transform from loader.cjs produces a valid result', () => {
-  const { transform: t } = require('./loader.cjs')
-  const { code, cssRules } = t('test.jsx', `const x = css({ color: 'red' })`)
-  assert(typeof code === 'string', 'code should be a string')
-  assert(Array.isArray(cssRules), 'cssRules should be an array')
+test('const arithmetic and chained consts', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const unit = 4
+    const double = unit * 2
+    const x = css({ margin: double + 1 })
+  `
+  const { cssRules } = transform('test.jsx', src)
+  assert(cssRules[0].css.includes('margin:9px'), `expected margin:9px, got: ${cssRules[0].css}`)
+})
+
+test('const template literal', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const border = 1
+    const line = \`\${border}px solid red\`
+    const x = css({ border: line })
+  `
+  const { cssRules } = transform('test.jsx', src)
+  assert(cssRules[0].css.includes('1px solid red'), `expected border in css, got: ${cssRules[0].css}`)
+})
+
+test('const object member access — SIZES.md', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const SIZES = { sm: 4, md: 8 }
+    const x = css({ gap: SIZES.md })
+  `
+  const { cssRules } = transform('test.jsx', src)
+  assert(cssRules[0].css.includes('gap:8px'), `expected gap:8px, got: ${cssRules[0].css}`)
+})
+
+test('as const object with static computed key', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const Colors = { primary: 'tomato', secondary: 'cyan' } as const
+    const x = css({ color: Colors['primary'] })
+  `
+  const { cssRules } = transform('test.tsx', src)
+  assert(cssRules[0].css.includes('tomato'), `expected tomato, got: ${cssRules[0].css}`)
+})
+
+test('enum-like map indexed by another const', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const Tone = { info: 'blue', danger: 'red' }
+    const CURRENT = 'danger'
+    const x = css({ color: Tone[CURRENT] })
+  `
+  const { cssRules } = transform('test.jsx', src)
+  assert(cssRules[0].css.includes('red'), `expected red, got: ${cssRules[0].css}`)
+})
+
+test('nested const objects and array index', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const tokens = { space: [0, 4, 8, 16] }
+    const x = css({ padding: tokens.space[3] })
+  `
+  const { cssRules } = transform('test.jsx', src)
+  assert(cssRules[0].css.includes('padding:16px'), `expected padding:16px, got: ${cssRules[0].css}`)
+})
+
+test('const used in globalCss interpolation without a theme', () => {
+  const src = "import { globalCss } from 'taikocss/css'\nconst base = 16; globalCss`html { font-size: ${base}px; }`"
+  const { globalCss } = transform('test.jsx', src)
+  assert(globalCss[0].css.includes('16px'), `expected 16px, got: ${globalCss[0].css}`)
+})
+
+test('let binding → clear build error', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    let gutter = 12
+    const x = css({ padding: gutter })
+  `
+  assertThrows(() => transform('test.jsx', src), /gutter.*let/, 'let binding error')
+})
+
+test('reassigned binding → clear build error', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    var gutter = 12
+    gutter = 16
+    const x = css({ padding: gutter })
+  `
+  assertThrows(() => transform('test.jsx', src), /gutter.*reassigned/, 'reassigned binding error')
+})
+
+test('missing property on const object → build error naming the key', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const SIZES = { sm: 4 }
+    const x = css({ gap: SIZES.xl })
+  `
+  assertThrows(() => transform('test.jsx', src), /SIZES.*xl/, 'missing key error')
+})
+
+test('circular consts → build error instead of a hang', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const a = b + 1
+    const b = a + 1
+    const x = css({ margin: a })
+  `
+  assertThrows(() => transform('test.jsx', src), /circular/i, 'cycle error')
+})
+
+test('const object used as a scalar value → build error', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const SIZES = { sm: 4 }
+    const x = css({ gap: SIZES })
+  `
+  assertThrows(() => transform('test.jsx', src), /is an object; only string, number and list values can be used here/, 'object value error')
+})
+
+test('const declared inside a function body is still a runtime variable', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    function f() {
+      const local = 'red'
+      return css({ color: local })
+    }
+  `
+  assertThrows(() => transform('test.jsx', src), /runtime variable/, 'local const error')
+})
+
+test('member lookup through object spreads, in both orders', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const A = { x: 5, y: 2 }
+    const B = { x: 1, ...A }
+    const C = { ...A, y: 9 }
+    const D = { ...{ z: 3 }, ...C }
+    const x = css({ margin: B.x, padding: C.x, top: C.y, left: D.z })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('margin:5px'), `later spread wins: ${css}`)
+  assert(css.includes('padding:5px'), `key from an earlier spread: ${css}`)
+  assert(css.includes('top:9px'), `later key wins over the spread: ${css}`)
+  assert(css.includes('left:3px'), `nested and literal spreads: ${css}`)
+})
+
+test('member lookup through an imported spread', () => {
+  const modules = withModules('/src/Button.jsx', { './tokens': '/src/tokens.js' }, {
+    '/src/tokens.js': { code: `const base = { md: 6 }\nexport const radii = { ...base, lg: 12 }`, imports: {} },
+  })
+  const src = `
+    import { css } from 'taikocss/css'
+    import { radii } from './tokens'
+    const x = css({ borderRadius: radii.md })
+  `
+  const { cssRules } = transform('/src/Button.jsx', src, null, null, modules)
+  assert(cssRules[0].css.includes('border-radius:6px'), `got: ${cssRules[0].css}`)
+})
+
+test('member lookup through a runtime spread — error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst B = { ...window.defaults, y: 1 }\nconst x = css({ margin: B.x })`),
+    /test\.jsx:2:16: css\(\) — '\.\.\.window\.defaults' is not a static object, so 'x' cannot be looked up/,
+    'runtime spread'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst A = { y: 1 }\nconst B = { ...A }\nconst x = css({ margin: B.x })`),
+    /'B' has no static property 'x'/,
+    'missing key'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst A = { ...A }\nconst x = css({ margin: A.x })`),
+    /circular constant reference/,
+    'self spread'
+  )
+})
+
+console.log('\n── Import & scope resolution ──────────────────────────────────')
+
+test('aliased import — import { css as style } is extracted', () => {
+  const src = `
+    import { css as style } from 'taikocss/css'
+    const x = style({ color: 'red' })
+  `
+  const { code, cssRules } = transform('test.jsx', src)
   assert(cssRules.length === 1, `expected 1 rule, got ${cssRules.length}`)
+  assert(!code.includes('style({'), `style() call should be replaced, got: ${code}`)
 })
 
-console.log('\n── v4: plugin.js ──────────────────────────────────────────────')
+test('namespace import — t.css({}) is extracted', () => {
+  const src = `
+    import * as t from 'taikocss/css'
+    const x = t.css({ color: 'red' })
+  `
+  const { code, cssRules } = transform('test.jsx', src)
+  assert(cssRules.length === 1, `expected 1 rule, got ${cssRules.length}`)
+  assert(!code.includes('t.css('), `t.css() call should be replaced, got: ${code}`)
+})
 
-test('taiko() returns a plugin object with the required hooks', async () => {
-  const { taiko } = await import('./plugin.js')
-  const plugin = taiko()
-  assert(typeof plugin.name === 'string', 'plugin.name should be a string')
-  assert(plugin.enforce === 'pre', 'plugin.enforce should be "pre"')
-  assert(typeof plugin.transform === 'function', 'plugin.transform should be a function')
-  assert(typeof plugin.resolveId === 'function', 'plugin.resolveId should be a function')
-  assert(typeof plugin.load === 'function', 'plugin.load should be a function')
-  assert(typeof plugin.handleHotUpdate === 'function', 'plugin.handleHotUpdate should be a function')
-  assert(typeof plugin.buildStart === 'function', 'plugin.buildStart should be a function')
+test('namespace import — keyframes and globalCss tags are extracted', () => {
+  const src = `
+    import * as t from 'taikocss/css'
+    const fade = t.keyframes\`from { opacity: 0; } to { opacity: 1; }\`
+    t.globalCss\`body { margin: 0; }\`
+  `
+  const { keyframes, globalCss } = transform('test.jsx', src)
+  assert(keyframes.length === 1, `expected 1 keyframe rule, got ${keyframes.length}`)
+  assert(globalCss.length === 1, `expected 1 globalCss rule, got ${globalCss.length}`)
 })
 
-test('taiko() with no arguments does not throw', async () => {
-  const { taiko } = await import('./plugin.js')
-  let threw = false
-  try { taiko() } catch { threw = true }
-  assert(!threw, 'taiko() with no args should not throw')
+test('local function named css — left untouched', () => {
+  const src = `
+    function css(x) { return x }
+    const x = css({ color: 'red' })
+  `
+  const { code, cssRules } = transform('test.jsx', src)
+  assert(cssRules.length === 0, `expected 0 rules, got ${cssRules.length}`)
+  assert(code === src, `source should be unchanged, got: ${code}`)
 })
 
-test('taiko({ theme }) passes theme through to transform — colour resolved in output', async () => {
-  const { taiko } = await import('./plugin.js')
-  const theme = { colors: { primary: 'tomato' } }
-  const plugin = taiko({ theme })
-  const src = `const x = css(({ theme }) => ({ color: theme.colors.primary }))`
-  // Call the transform hook directly (simulate Vite)
-  const result = plugin.transform(src, 'test.jsx')
-  assert(result !== null && result !== undefined, 'expected a transform result, got null/undefined')
-  assert(typeof result.code === 'string', 'result.code should be a string')
-  assert(!result.code.includes('css('), 'css() call should be replaced')
-  assert(result.code.includes('"cls_'), 'should contain a cls_ class name')
+test('css imported from another module — left untouched', () => {
+  const src = `
+    import { css } from '@emotion/css'
+    const x = css({ color: 'red' })
+  `
+  const { cssRules } = transform('test.jsx', src)
+  assert(cssRules.length === 0, `expected 0 rules, got ${cssRules.length}`)
 })
 
-test('rustCssPlugin export is a valid plugin object', async () => {
-  const { rustCssPlugin } = await import('./plugin.js')
-  assert(typeof rustCssPlugin === 'object' && rustCssPlugin !== null, 'rustCssPlugin should be an object')
-  assert(typeof rustCssPlugin.name === 'string', 'rustCssPlugin.name should be a string')
-  assert(typeof rustCssPlugin.transform === 'function', 'rustCssPlugin.transform should be a function')
+test('css() without an import — left untouched', () => {
+  const src = `const x = css({ fontSize: rem(14) })`
+  const { code, cssRules } = transform('test.jsx', src)
+  assert(cssRules.length === 0, `expected 0 rules, got ${cssRules.length}`)
+  assert(code === src, `source should be unchanged, got: ${code}`)
 })
 
-console.log('\n── v4: css shim (src/css.ts compiled) ─────────────────────────')
+test('shadowed css parameter inside a function — left untouched', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const a = css({ color: 'red' })
+    function render(css) { return css({ color: 'blue' }) }
+  `
+  const { code, cssRules } = transform('test.jsx', src)
+  assert(cssRules.length === 1, `expected 1 rule, got ${cssRules.length}`)
+  assert(code.includes("css({ color: 'blue' })"), `shadowed call should remain, got: ${code}`)
+})
 
-test('src/css.ts shim — css() returns an empty string', async () => {
-  // Import the compiled JS shim (tsc must have run, or we test the TS source via tsx/ts-node)
-  // We test the contract: the shim must return '' and not throw.
-  // Dynamic import of a .ts file only works if tsx/ts-node is present;
-  // guard gracefully if not available.
-  let cssShim
-  try {
-    cssShim = await import('./src/css.ts')
-  } catch {
-    try {
-      cssShim = await import('./src/css.js')
-    } catch {
-      console.log('    (skipped — shim not compiled yet; run tsc first)')
-      return
+test('import is removed once every call site has been replaced', () => {
+  const src = `
+    import { css, keyframes } from 'taikocss/css'
+    const fade = keyframes\`from { opacity: 0; } to { opacity: 1; }\`
+    const x = css({ color: 'red' })
+  `
+  const { code } = transform('test.jsx', src)
+  assert(!code.includes('taikocss/css'), `import should be removed, got: ${code}`)
+})
+
+test('container() inside a replaced css() counts as consumed', () => {
+  const src = `
+    import { css, container } from 'taikocss/css'
+    const x = css({ ...container('inline-size') })
+  `
+  const { code } = transform('test.jsx', src)
+  assert(!code.includes('taikocss/css'), `import should be removed, got: ${code}`)
+})
+
+test('import keeps specifiers that are still referenced', () => {
+  const src = `
+    import { css, container } from 'taikocss/css'
+    const x = css({ color: 'red' })
+    const c = container
+  `
+  const { code } = transform('test.jsx', src)
+  assert(code.includes('import { container } from'), `container should be kept, got: ${code}`)
+  assert(!code.includes('css,'), `css specifier should be dropped, got: ${code}`)
+})
+
+console.log('\n── Traversal coverage ─────────────────────────────────────────')
+
+function assertExtracted(src, filename = 'test.jsx', expected = 1) {
+  const { code, cssRules } = transform(filename, src)
+  assert(cssRules.length === expected, `expected ${expected} rule(s), got ${cssRules.length}: ${code}`)
+  assert(!code.includes('css({'), `css() call should be replaced, got: ${code}`)
+  return code
+}
+
+test('class static property — static styles = css(...)', () => {
+  assertExtracted(`import { css } from 'taikocss/css'\nclass Card { static styles = css({ color: 'red' }) }`)
+})
+
+test('class instance property and method body', () => {
+  assertExtracted(`
+    import { css } from 'taikocss/css'
+    class Card {
+      root = css({ color: 'red' })
+      render() { return css({ color: 'blue' }) }
+    }
+  `, 'test.jsx', 2)
+})
+
+test('object literal value', () => {
+  assertExtracted(`import { css } from 'taikocss/css'\nconst styles = { root: css({ color: 'red' }), label: css({ color: 'blue' }) }`, 'test.jsx', 2)
+})
+
+test('array element', () => {
+  assertExtracted(`import { css } from 'taikocss/css'\nconst list = [css({ color: 'red' }), css({ color: 'blue' })]`, 'test.jsx', 2)
+})
+
+test('ternary branches', () => {
+  assertExtracted(`import { css } from 'taikocss/css'\nconst x = cond ? css({ color: 'red' }) : css({ color: 'blue' })`, 'test.jsx', 2)
+})
+
+test('logical expression', () => {
+  assertExtracted(`import { css } from 'taikocss/css'\nconst x = active && css({ color: 'red' })`)
+})
+
+test('for loop body', () => {
+  assertExtracted(`import { css } from 'taikocss/css'\nfor (let i = 0; i < 1; i++) { const x = css({ color: 'red' }) }`)
+})
+
+test('switch case', () => {
+  assertExtracted(`
+    import { css } from 'taikocss/css'
+    function pick(v) {
+      switch (v) {
+        case 'a': return css({ color: 'red' })
+        default: return css({ color: 'blue' })
+      }
+    }
+  `, 'test.jsx', 2)
+})
+
+test('try / catch / finally blocks', () => {
+  assertExtracted(`
+    import { css } from 'taikocss/css'
+    try { a = css({ color: 'red' }) }
+    catch (e) { b = css({ color: 'blue' }) }
+    finally { c = css({ color: 'green' }) }
+  `, 'test.jsx', 3)
+})
+
+test('JSX fragment child', () => {
+  assertExtracted(`import { css } from 'taikocss/css'\nconst el = <><div className={css({ color: 'red' })} /></>`)
+})
+
+test('nested JSX child element attribute', () => {
+  assertExtracted(`
+    import { css } from 'taikocss/css'
+    const el = (
+      <section>
+        <div>
+          <span className={css({ color: 'red' })}>hi</span>
+        </div>
+      </section>
+    )
+  `)
+})
+
+test('module.exports = css(...)', () => {
+  assertExtracted(`import { css } from 'taikocss/css'\nmodule.exports = css({ color: 'red' })`, 'test.js')
+})
+
+test('callback argument of another call', () => {
+  assertExtracted(`import { css } from 'taikocss/css'\nitems.map(() => css({ color: 'red' }))`)
+})
+
+test('default parameter value', () => {
+  assertExtracted(`import { css } from 'taikocss/css'\nfunction f(cls = css({ color: 'red' })) { return cls }`)
+})
+
+test('keyframes inside a function body — name still resolved in css()', () => {
+  const src = `
+    import { css, keyframes } from 'taikocss/css'
+    function make() {
+      const fade = keyframes\`from { opacity: 0; } to { opacity: 1; }\`
+      return css({ animation: \`\${fade} 1s\` })
     }
+  `
+  const { cssRules, keyframes } = transform('test.jsx', src)
+  assert(keyframes.length === 1, `expected 1 keyframe rule, got ${keyframes.length}`)
+  assert(cssRules[0].css.includes(keyframes[0].name), `expected ${keyframes[0].name} in css, got: ${cssRules[0].css}`)
+})
+
+test('error inside a nested position still reports file/line info', () => {
+  assertThrows(
+    () => transform('src/Deep.tsx', `import { css } from 'taikocss/css'\nconst s = { a: [cond ? css({ color: someVar }) : null] }`),
+    /src\/Deep\.tsx:\d+:\d+/,
+    'nested dynamic value error'
+  )
+})
+
+console.log('\n── Cross-module evaluation ────────────────────────────────────')
+
+// Module map as the Vite plugin builds it: resolved id → { code, imports }
+function withModules(entry, imports, modules) {
+  return { ...modules, [entry]: { code: '', imports } }
+}
+
+test('named import of a const from another module is folded', () => {
+  const modules = withModules('/src/Button.jsx', { './tokens': '/src/tokens.js' }, {
+    '/src/tokens.js': { code: `export const radii = { md: 6 }`, imports: {} },
+  })
+  const src = `
+    import { css } from 'taikocss/css'
+    import { radii } from './tokens'
+    const x = css({ borderRadius: radii.md })
+  `
+  const { cssRules } = transform('/src/Button.jsx', src, null, null, modules)
+  assert(cssRules[0].css.includes('border-radius:6px'), `expected 6px, got: ${cssRules[0].css}`)
+})
+
+test('default import of a JSON module is folded', () => {
+  const modules = withModules('/src/Button.jsx', { './tokens.json': '/src/tokens.json' }, {
+    '/src/tokens.json': { code: `{ "colors": { "brand": "#0af" } }`, imports: {} },
+  })
+  const src = `
+    import { css } from 'taikocss/css'
+    import tokens from './tokens.json'
+    const x = css({ color: tokens.colors.brand })
+  `
+  const { cssRules } = transform('/src/Button.jsx', src, null, null, modules)
+  assert(cssRules[0].css.includes('#0af'), `expected #0af, got: ${cssRules[0].css}`)
+})
+
+test('namespace import and aliased import are folded', () => {
+  const modules = withModules('/src/Button.jsx', { './tokens': '/src/tokens.js' }, {
+    '/src/tokens.js': { code: `export const space = [0, 4, 8]; export const brand = 'teal'`, imports: {} },
+  })
+  const src = `
+    import { css } from 'taikocss/css'
+    import * as t from './tokens'
+    import { brand as accent } from './tokens'
+    const x = css({ padding: t.space[2], color: accent })
+  `
+  const { cssRules } = transform('/src/Button.jsx', src, null, null, modules)
+  assert(cssRules[0].css.includes('padding:8px'), `expected 8px, got: ${cssRules[0].css}`)
+  assert(cssRules[0].css.includes('teal'), `expected teal, got: ${cssRules[0].css}`)
+})
+
+test('values resolve through re-exports, export * and imported consts', () => {
+  const modules = withModules('/src/Button.jsx', { './theme': '/src/theme/index.js' }, {
+    '/src/theme/index.js': {
+      code: `export { gap as gutter } from './space'; export * from './colors'`,
+      imports: { './space': '/src/theme/space.js', './colors': '/src/theme/colors.js' },
+    },
+    '/src/theme/space.js': { code: `import { base } from './base'; export const gap = base * 2`, imports: { './base': '/src/theme/base.js' } },
+    '/src/theme/base.js': { code: `export const base = 6`, imports: {} },
+    '/src/theme/colors.js': { code: `export const ink = '#111'`, imports: {} },
+  })
+  const src = `
+    import { css } from 'taikocss/css'
+    import { gutter, ink } from './theme'
+    const x = css({ margin: gutter, color: ink })
+  `
+  const { cssRules, dependencies } = transform('/src/Button.jsx', src, null, null, modules)
+  assert(cssRules[0].css.includes('margin:12px'), `expected 12px, got: ${cssRules[0].css}`)
+  assert(cssRules[0].css.includes('#111'), `expected #111, got: ${cssRules[0].css}`)
+  for (const dep of ['/src/theme/index.js', '/src/theme/space.js', '/src/theme/base.js', '/src/theme/colors.js']) {
+    assert(dependencies.includes(dep), `expected ${dep} in dependencies, got: ${dependencies}`)
   }
-  assert(typeof cssShim.css === 'function', 'css should be a function')
-  assert(cssShim.css({ color: 'red' }) === '', 'css() shim should return ""')
-  assert(typeof cssShim.globalCss === 'function', 'globalCss should be a function')
-  assert(typeof cssShim.keyframes === 'function', 'keyframes should be a function')
-  assert(typeof cssShim.container === 'function', 'container should be a function')
-  assert(cssShim.keyframes`from{opacity:0}to{opacity:1}` === '', 'keyframes shim should return ""')
-  const containerResult = cssShim.container('sidebar', 'inline-size')
-  assert(typeof containerResult === 'object', 'container() shim should return an object')
+})
+
+test('exported style object — css(base) and nested under a selector', () => {
+  const modules = withModules('/src/Button.jsx', { './styles': '/src/styles.js' }, {
+    '/src/styles.js': {
+      code: `const accent = 'crimson'; export const base = { color: accent }; export const hover = { opacity: 0.5 }`,
+      imports: {},
+    },
+  })
+  const src = `
+    import { css } from 'taikocss/css'
+    import { base, hover } from './styles'
+    const a = css(base)
+    const b = css({ padding: 4, '&:hover': hover })
+  `
+  const { code, cssRules } = transform('/src/Button.jsx', src, null, null, modules)
+  assert(cssRules.length === 2, `expected 2 rules, got ${cssRules.length}`)
+  assert(cssRules[0].css.includes('color:#dc143c'), `expected crimson, got: ${cssRules[0].css}`)
+  assert(cssRules[1].css.includes(':hover{opacity:.5}'), `expected nested hover rule, got: ${cssRules[1].css}`)
+  assert(!code.includes('css('), `css() calls should be replaced, got: ${code}`)
+})
+
+test('exported keyframes binding resolves to the same animation name', () => {
+  const animations = `
+    import { keyframes } from 'taikocss/css'
+    export const fadeIn = keyframes\`from { opacity: 0; } to { opacity: 1; }\`
+  `
+  const { keyframes } = transform('/src/animations.js', animations)
+  const modules = withModules('/src/Button.jsx', { './animations': '/src/animations.js' }, {
+    '/src/animations.js': { code: animations, imports: { 'taikocss/css': 'taikocss/css' } },
+  })
+  const src = `
+    import { css } from 'taikocss/css'
+    import { fadeIn } from './animations'
+    const x = css({ animation: \`\${fadeIn} 1s ease\` })
+  `
+  const { cssRules } = transform('/src/Button.jsx', src, null, null, modules)
+  assert(cssRules[0].css.includes(keyframes[0].name), `expected ${keyframes[0].name}, got: ${cssRules[0].css}`)
+})
+
+test('local-only transform reports no dependencies', () => {
+  const { dependencies } = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ color: 'red' })`)
+  assert(Array.isArray(dependencies) && dependencies.length === 0, `expected [], got: ${dependencies}`)
+})
+
+test('unresolved import used in css() → build error naming the specifier', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    import { brand } from 'some-package'
+    const x = css({ color: brand })
+  `
+  assertThrows(() => transform('test.jsx', src), /brand.*some-package.*could not be resolved/, 'unresolved import error')
+})
+
+test('missing export → build error', () => {
+  const modules = withModules('/src/Button.jsx', { './tokens': '/src/tokens.js' }, {
+    '/src/tokens.js': { code: `export const a = 1`, imports: {} },
+  })
+  const src = `
+    import { css } from 'taikocss/css'
+    import { b } from './tokens'
+    const x = css({ margin: b })
+  `
+  assertThrows(() => transform('/src/Button.jsx', src, null, null, modules), /does not export 'b'/, 'missing export error')
+})
+
+test('let binding in another module → build error located in that module', () => {
+  const modules = withModules('/src/Button.jsx', { './tokens': '/src/tokens.js' }, {
+    '/src/tokens.js': { code: `export let gap = 4`, imports: {} },
+  })
+  const src = `
+    import { css } from 'taikocss/css'
+    import { gap } from './tokens'
+    const x = css({ margin: gap })
+  `
+  assertThrows(() => transform('/src/Button.jsx', src, null, null, modules), /\/src\/tokens\.js:\d+:\d+.*`let`/, 'let error')
+})
+
+test('collectImports lists value imports and re-exports, skipping taikocss/css and types', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    import type { Theme } from './theme'
+    import { radii } from './tokens'
+    import data from './data.json'
+    export * from './colors'
+    export { a } from './tokens'
+  `
+  const specs = collectImports('test.tsx', src)
+  assert(JSON.stringify(specs) === JSON.stringify(['./tokens', './data.json', './colors']), `got: ${JSON.stringify(specs)}`)
+})
+
+console.log('\n── Object spread ──────────────────────────────────────────────')
+
+test('spread of a local const style object', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const base = { color: 'red', padding: 4 }
+    const x = css({ ...base, margin: 2 })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('color:red') && css.includes('padding:4px') && css.includes('margin:2px'), `got: ${css}`)
+})
+
+test('later keys win and keep the position of the first occurrence', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const base = { color: 'red', padding: 4 }
+    const x = css({ ...base, color: 'blue' })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(!css.includes('red'), `overridden color should be dropped, got: ${css}`)
+  assert(css.indexOf('color:#00f') < css.indexOf('padding'), `color should stay first, got: ${css}`)
+})
+
+test('a later spread overrides earlier keys', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const base = { color: 'red' }
+    const danger = { color: 'tomato' }
+    const x = css({ color: 'blue', ...base, ...danger })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('color:tomato') && !css.includes('red') && !css.includes('#00f'), `got: ${css}`)
+})
+
+test('nested selectors are replaced, not deep-merged', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const base = { '&:hover': { color: 'red', opacity: 0.5 } }
+    const x = css({ ...base, '&:hover': { color: 'blue' } })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes(':hover{color:#00f}'), `expected replaced hover rule, got: ${css}`)
+  assert(!css.includes('opacity'), `earlier hover declarations should be dropped, got: ${css}`)
+})
+
+test('spread of a theme sub-object', () => {
+  const theme = JSON.stringify({ typography: { heading: { fontSize: 24, fontWeight: 700, '&:first-child': { marginTop: 0 } } } })
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ ...theme.typography.heading, color: 'red' }))`
+  const css = transform('test.jsx', src, theme).cssRules[0].css
+  assert(css.includes('font-size:24px') && css.includes('font-weight:700'), `got: ${css}`)
+  assert(css.includes(':first-child{margin-top:0}'), `expected nested rule, got: ${css}`)
+})
+
+test('spread of a ternary between two known objects', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const variant = 'primary'
+    const primary = { color: 'white' }
+    const ghost = { color: 'black' }
+    const a = css({ ...(variant ? primary : ghost) })
+    const b = css({ ...('' ? primary : ghost) })
+  `
+  const { cssRules } = transform('test.jsx', src)
+  assert(cssRules[0].css.includes('color:#fff'), `got: ${cssRules[0].css}`)
+  assert(cssRules[1].css.includes('color:#000'), `got: ${cssRules[1].css}`)
+})
+
+test('spread of an imported style object and of an inline object', () => {
+  const modules = {
+    '/src/Button.jsx': { code: '', imports: { './styles': '/src/styles.js' } },
+    '/src/styles.js': { code: `const ring = '2px solid teal'; export const focusRing = { outline: ring }`, imports: {} },
+  }
+  const src = `
+    import { css } from 'taikocss/css'
+    import { focusRing } from './styles'
+    const x = css({ ...focusRing, ...{ color: 'red' } })
+  `
+  const css = transform('/src/Button.jsx', src, null, null, modules).cssRules[0].css
+  assert(css.includes('outline:2px solid teal') && css.includes('color:red'), `got: ${css}`)
+})
+
+test('spread of a scalar const → build error', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const size = 4
+    const x = css({ ...size })
+  `
+  assertThrows(() => transform('test.jsx', src), /spread of 'size'/, 'scalar spread error')
+})
+
+console.log('\n── Conditional & logical values ───────────────────────────────')
+
+test('ternary with a static condition selects a branch', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const isDark = true
+    const x = css({ color: isDark ? 'white' : 'black', background: !isDark ? 'white' : 'black' })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('color:#fff') && css.includes('background:#000'), `got: ${css}`)
+})
+
+test('comparison operators in conditions', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const size = 'lg'
+    const columns = 12
+    const x = css({
+      fontSize: size === 'lg' ? 20 : 14,
+      gap: columns >= 12 ? 8 : 4,
+      margin: size !== 'sm' && columns < 16 ? 2 : 0,
+      padding: columns == '12' ? 1 : 0,
+    })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('font-size:20px') && css.includes('gap:8px'), `got: ${css}`)
+  assert(css.includes('margin:2px') && css.includes('padding:1px'), `got: ${css}`)
+})
+
+test('theme values in conditions', () => {
+  const theme = JSON.stringify({ mode: 'dark', dense: false })
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({
+    color: theme.mode === 'dark' ? 'white' : 'black',
+    padding: theme.dense ? 4 : 8,
+  }))`
+  const css = transform('test.jsx', src, theme).cssRules[0].css
+  assert(css.includes('color:#fff') && css.includes('padding:8px'), `got: ${css}`)
+})
+
+test('&& with a falsy condition omits the declaration', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const debug = false
+    const x = css({ color: 'red', outline: debug && '1px solid red' })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('color:red') && !css.includes('outline'), `got: ${css}`)
+})
+
+test('&& with a truthy condition keeps the declaration', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const debug = 1
+    const x = css({ outline: debug && '1px solid red' })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('outline:1px solid red'), `got: ${css}`)
+})
+
+test('|| and ?? fall back to the right operand', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const accent = ''
+    const brand = null
+    const x = css({ color: accent || 'teal', background: brand ?? 'navy', borderColor: 'red' ?? 'blue' })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('color:teal') && css.includes('background:navy') && css.includes('border-color:red'), `got: ${css}`)
+})
+
+test('null, undefined and false values omit the declaration', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ color: 'red', margin: null, padding: undefined, border: false })`
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('color:red'), `got: ${css}`)
+  assert(!css.includes('margin') && !css.includes('padding') && !css.includes('border'), `got: ${css}`)
+})
+
+test('an omitted value still overrides the same key from a spread', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const base = { color: 'red', padding: 4 }
+    const x = css({ ...base, color: null })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(!css.includes('color') && css.includes('padding:4px'), `got: ${css}`)
+})
+
+test('conditional nested rule and conditional spread', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const interactive = false
+    const hover = { opacity: 0.5 }
+    const x = css({
+      color: 'red',
+      '&:hover': interactive ? hover : null,
+      ...(interactive && { cursor: 'pointer' }),
+      ...(!interactive && { cursor: 'default' }),
+    })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(!css.includes(':hover') && css.includes('cursor:default'), `got: ${css}`)
+})
+
+test('the unselected branch is not evaluated', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const ok = true
+    const x = css({ color: ok ? 'red' : someRuntimeValue })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('color:red'), `got: ${css}`)
+})
+
+test('runtime condition → build error', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ color: props.active ? 'red' : 'blue' })`
+  assertThrows(() => transform('test.jsx', src), /runtime variable/, 'runtime condition error')
+})
+
+test('a value of true → build error', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ color: 1 < 2 })`
+  assertThrows(() => transform('test.jsx', src), /evaluates to `true`/, 'true value error')
+})
+
+console.log('\n── Build-time defines ─────────────────────────────────────────')
+
+test('process.env.NODE_ENV comparison resolves from defines', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ outline: process.env.NODE_ENV === 'production' ? 'none' : '1px dashed magenta' })`
+  const prod = transform('test.jsx', src, null, null, null, { 'process.env.NODE_ENV': '"production"' })
+  const dev = transform('test.jsx', src, null, null, null, { 'process.env.NODE_ENV': '"development"' })
+  assert(prod.cssRules[0].css.includes('outline:none'), `got: ${prod.cssRules[0].css}`)
+  assert(dev.cssRules[0].css.includes('dashed'), `got: ${dev.cssRules[0].css}`)
+})
+
+test('import.meta.env lookups resolve through an object define', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ color: import.meta.env.VITE_BRAND, outline: import.meta.env.VITE_DEBUG && '1px solid red' })`
+  const defines = { 'import.meta.env': JSON.stringify({ VITE_BRAND: 'teal' }) }
+  const css = transform('test.jsx', src, null, null, null, defines).cssRules[0].css
+  assert(css.includes('color:teal'), `got: ${css}`)
+  assert(!css.includes('outline'), `missing env key should be undefined and omit the declaration, got: ${css}`)
+})
+
+test('the longest defined prefix wins', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ color: import.meta.env.VITE_BRAND })`
+  const defines = {
+    'import.meta.env': JSON.stringify({ VITE_BRAND: 'teal' }),
+    'import.meta.env.VITE_BRAND': '"crimson"',
+  }
+  const css = transform('test.jsx', src, null, null, null, defines).cssRules[0].css
+  assert(css.includes('color:#dc143c'), `got: ${css}`)
+})
+
+test('a local binding shadows a define', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const process = { env: { NODE_ENV: 'test' } }
+    const x = css({ content: process.env.NODE_ENV === 'test' ? '"a"' : '"b"' })
+  `
+  const css = transform('test.jsx', src, null, null, null, { 'process.env.NODE_ENV': '"production"' }).cssRules[0].css
+  assert(css.includes('"a"'), `got: ${css}`)
+})
+
+test('undefined define without a map → build error', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ color: process.env.BRAND })`
+  assertThrows(() => transform('test.jsx', src), /runtime variable/, 'no define error')
+})
+
+test('a define value that is not JSON → error naming the key', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ color: 'red' })`, null, null, null, { 'process.env.BRAND': 'acme' }),
+    /process\.env\.BRAND.*not valid JSON/,
+    'invalid define error'
+  )
+})
+
+console.log('\n── Compile-time helpers ───────────────────────────────────────')
+
+test('rem(), em() and px() convert units', () => {
+  const src = `
+    import { css, rem, em, px } from 'taikocss/css'
+    const x = css({ fontSize: rem(14), letterSpacing: em(1, 20), margin: px(3) })
+  `
+  const { code, cssRules } = transform('test.jsx', src)
+  const css = cssRules[0].css
+  assert(css.includes('font-size:.875rem') && css.includes('letter-spacing:.05em') && css.includes('margin:3px'), `got: ${css}`)
+  assert(!code.includes('taikocss/css'), `helper import should be dropped with css(), got: ${code}`)
+})
+
+test('px() of a theme expression', () => {
+  const theme = JSON.stringify({ spacing: { unit: 8 } })
+  const src = `import { css, px } from 'taikocss/css'\nconst x = css(({ theme }) => ({ padding: px(theme.spacing.unit * 2) }))`
+  const css = transform('test.jsx', src, theme).cssRules[0].css
+  assert(css.includes('padding:16px'), `got: ${css}`)
+})
+
+test('fluid() emits a clamp() between the two sizes', () => {
+  const src = `import { css, fluid } from 'taikocss/css'\nconst x = css({ fontSize: fluid(14, 20) })`
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('clamp(.875rem,.75rem + .625vw,1.25rem)'), `got: ${css}`)
+})
+
+test('Math.min/max/round/floor/ceil/abs', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const base = 7
+    const x = css({
+      width: Math.max(base * 10, 64),
+      height: Math.min(base, 3, 5),
+      zIndex: Math.round(2.5),
+      top: Math.floor(base / 2),
+      left: Math.ceil(base / 2),
+      right: Math.abs(base - 9),
+    })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  for (const part of ['width:70px', 'height:3px', 'z-index:3', 'top:3px', 'left:4px', 'right:2px']) {
+    assert(css.includes(part), `expected ${part}, got: ${css}`)
+  }
+})
+
+test('helpers registered from config expand their template', () => {
+  const helpers = { clampType: 'var(--font-size-{0})', space: 'calc({0} * var(--space-{1}))' }
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ fontSize: clampType('body'), gap: space(2, 'md') })`
+  const css = transform('test.jsx', src, null, null, null, null, helpers).cssRules[0].css
+  assert(css.includes('font-size:var(--font-size-body)'), `got: ${css}`)
+  assert(css.includes('gap:calc(2 * var(--space-md))'), `got: ${css}`)
+})
+
+test('a local function shadows a helper of the same name', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    function rem(x) { return x }
+    const x = css({ fontSize: rem(14) })
+  `
+  assertThrows(() => transform('test.jsx', src), /call to 'rem'/, 'shadowed helper error')
+})
+
+test('helper arity error is reported at the call site', () => {
+  assertThrows(
+    () => transform('src/A.tsx', `import { css, rem } from 'taikocss/css'\nconst x = css({ fontSize: rem(14, 2) })`),
+    /src\/A\.tsx:2:27: css\(\) — rem\(\) expects 1 argument, got 2/,
+    'arity error'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ gap: space(1) })`, null, null, null, null, { space: '{0} {1}' }),
+    /space\(\) expects 2 arguments, got 1/,
+    'template arity error'
+  )
+})
+
+test('helper type error names the argument', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css, rem } from 'taikocss/css'\nconst x = css({ fontSize: rem('14px') })`),
+    /rem\(\) argument 1 must be a number, got the string '14px'/,
+    'type error'
+  )
+})
+
+test('unknown function call → build error with a hint', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ color: darkenColor('red') })`),
+    /call to 'darkenColor'[\s\S]*Hint:/,
+    'unknown call error'
+  )
+})
+
+test('helpers, arithmetic and literals format numbers alike', async () => {
+  const cssShim = await import('./src/css.js')
+  const src = `import { css, em, px } from 'taikocss/css'
+    const x = css({ content: \`"\${em(1, 3)} \${1 / 3} \${px(0.1 + 0.2)} \${0.30000000000000004}"\` })`
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('content:"0.3333333333em 0.3333333333 0.3px 0.3"'), `got: ${css}`)
+  assert(cssShim.em(1, 3) === '0.3333333333em' && cssShim.px(0.1 + 0.2) === '0.3px',
+    `shim: ${cssShim.em(1, 3)} ${cssShim.px(0.1 + 0.2)}`)
+})
+
+console.log('\n── Colour functions ───────────────────────────────────────────')
+
+test('darken() and lighten() adjust OKLCH lightness of a theme colour', () => {
+  const theme = JSON.stringify({ colors: { primary: '#3366ff' } })
+  const src = `
+    import { css, darken, lighten } from 'taikocss/css'
+    const x = css(({ theme }) => ({
+      color: theme.colors.primary,
+      '&:hover': { color: darken(theme.colors.primary, 0.1) },
+      '&:focus': { color: lighten(theme.colors.primary, 0.1) },
+    }))
+  `
+  const { code, cssRules } = transform('test.jsx', src, theme)
+  const css = cssRules[0].css
+  assert(css.includes(':hover{color:#1a43dc}'), `got: ${css}`)
+  assert(/:focus\{color:#[0-9a-f]{3,6}\}/.test(css) && !css.includes(':focus{color:#36f}'), `got: ${css}`)
+  assert(!code.includes('taikocss/css'), `helper import should be dropped, got: ${code}`)
+})
+
+test('alpha() sets the opacity', () => {
+  const css = transform('test.jsx', `import { css, alpha } from 'taikocss/css'\nconst x = css({ background: alpha('#3366ff', 0.5) })`).cssRules[0].css
+  assert(css.includes('background:#3366ff80'), `got: ${css}`)
+})
+
+test('mix() interpolates two colours in OKLab', () => {
+  const css = transform('test.jsx', `import { css, mix } from 'taikocss/css'\nconst x = css({ color: mix('red', 'blue', 0.3), background: mix('white', 'black') })`).cssRules[0].css
+  assert(css.includes('color:#ba4d79'), `got: ${css}`)
+  assert(/background:#[0-9a-f]{3,6}/.test(css), `got: ${css}`)
+})
+
+test('contrastText() picks black or white', () => {
+  const src = `import { css, contrastText } from 'taikocss/css'\nconst x = css({ color: contrastText('#ffeb3b'), background: contrastText('oklch(0.3 0.1 260)') })`
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('color:#000') && css.includes('background:#fff'), `got: ${css}`)
+})
+
+test('wide-gamut input stays wide-gamut, with an sRGB fallback for the targets', () => {
+  const css = transform('test.jsx', `import { css, darken } from 'taikocss/css'\nconst x = css({ color: darken('oklch(0.6 0.2 250)', 0.1) })`).cssRules[0].css
+  assert(/color:#[0-9a-f]{6};color:lab\(/.test(css), `got: ${css}`)
+})
+
+test('invalid colour → build error at the argument', () => {
+  assertThrows(
+    () => transform('src/A.tsx', `import { css, darken } from 'taikocss/css'\nconst x = css({ color: darken('notacolor', 0.1) })`),
+    /src\/A\.tsx:2:31: css\(\) — darken\(\) argument 1 is not a valid CSS colour: 'notacolor'/,
+    'invalid colour error'
+  )
+})
+
+test('colour amount outside 0–1 → build error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css, alpha } from 'taikocss/css'\nconst x = css({ color: alpha('red', 50) })`),
+    /alpha\(\) argument 2 must be between 0 and 1, got 50/,
+    'range error'
+  )
+})
+
+test('runtime colours need color-mix() support in every target', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css, alpha } from 'taikocss/css'\nconst x = css({ color: alpha('var(--brand)', 0.5) })`),
+    /alpha\(\) needs a colour known at build time[\s\S]*color-mix\(\)/,
+    'runtime colour error'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css, contrastText } from 'taikocss/css'\nconst x = css({ color: contrastText('currentColor') })`),
+    /contrastText\(\) needs a colour known at build time, got 'currentColor'/,
+    'runtime contrast error'
+  )
+})
+
+test('runtime colours pass through when the targets support them', async () => {
+  const cssShim = await import('./src/css.js')
+  const src = (value) => `import { css, alpha, mix, darken } from 'taikocss/css'\nconst x = css({ color: ${value} })`
+  const colour = (value, targets) => transform('test.jsx', src(value), null, null, null, null, null, targets)
+    .cssRules[0].css.match(/\{color:(.*)\}$/)[1]
+  const colorMix = { chrome: '111', safari: '16.2', firefox: '113' }
+  assert(colour(`alpha('var(--brand)', 0.5)`, colorMix) === cssShim.alpha('var(--brand)', 0.5),
+    `alpha: ${colour(`alpha('var(--brand)', 0.5)`, colorMix)}`)
+  assert(colour(`mix('var(--a)', 'red', 0.3)`, colorMix) === cssShim.mix('var(--a)', 'red', 0.3),
+    `mix: ${colour(`mix('var(--a)', 'red', 0.3)`, colorMix)}`)
+  assertThrows(
+    () => colour(`darken('var(--brand)', 0.1)`, colorMix),
+    /darken\(\) needs a colour known at build time[\s\S]*relative colour syntax/,
+    'relative colours need newer targets'
+  )
+  const relative = { chrome: '119', safari: '18', firefox: '128' }
+  assert(colour(`darken('var(--brand)', 0.1)`, relative) === 'oklch(from var(--brand) calc(l - .1) c h)',
+    `darken: ${colour(`darken('var(--brand)', 0.1)`, relative)}`)
+  // Static colours are still computed
+  assert(colour(`alpha('#ff0000', 0.5)`, relative) === '#ff000080', `static: ${colour(`alpha('#ff0000', 0.5)`, relative)}`)
+})
+
+test('invalid browser targets → error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ color: 'red' })`, null, null, null, null, null, { netscape: '4' }),
+    /test\.jsx: targets — unknown browser 'netscape'/,
+    'unknown browser'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ color: 'red' })`, null, null, null, null, null, { safari: 'latest' }),
+    /'latest' is not a version for 'safari'/,
+    'bad version'
+  )
+})
+
+console.log('\n── Dimension arithmetic ───────────────────────────────────────')
+
+const DIM_THEME = JSON.stringify({
+  spacing: { md: '1rem', gutter: '4px', half: '50%', gap: 'var(--gap)' },
+  motion: { fast: '150ms' },
+  border: '1px solid',
+})
+
+test('dimension tokens keep their unit through * and /', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ padding: theme.spacing.md * 2, margin: theme.spacing.gutter / 2, width: theme.spacing.half * 2 }))`
+  const css = transform('test.jsx', src, DIM_THEME).cssRules[0].css
+  assert(css.includes('padding:2rem') && css.includes('margin:2px') && css.includes('width:100%'), `got: ${css}`)
+})
+
+test('like units fold, absolute units convert', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({
+    padding: theme.spacing.md + '0.5rem',
+    transitionDuration: theme.motion.fast + '0.1s',
+    margin: '12pt' - theme.spacing.gutter,
+  }))`
+  const css = transform('test.jsx', src, DIM_THEME).cssRules[0].css
+  for (const part of ['padding:1.5rem', 'transition-duration:.25s', 'margin:9pt']) {
+    assert(css.includes(part), `expected ${part}, got: ${css}`)
+  }
+})
+
+test('mixed lengths and percentages become calc()', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ width: theme.spacing.half - theme.spacing.md, height: (theme.spacing.md + theme.spacing.gutter) * 2 }))`
+  const css = transform('test.jsx', src, DIM_THEME).cssRules[0].css
+  assert(css.includes('width:calc(50% - 1rem)'), `got: ${css}`)
+  assert(css.includes('height:calc(2rem + 8px)'), `got: ${css}`)
+})
+
+test('var() operands become calc()', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ gap: theme.spacing.gap * 2 }))`
+  const css = transform('test.jsx', src, DIM_THEME).cssRules[0].css
+  assert(css.includes('gap:calc(var(--gap) * 2)'), `got: ${css}`)
+})
+
+test('dividing like dimensions gives a ratio', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ flexGrow: '2rem' / theme.spacing.md, opacity: '1in' / '192px' }))`
+  const css = transform('test.jsx', src, DIM_THEME).cssRules[0].css
+  assert(css.includes('flex-grow:2') && css.includes('opacity:.5'), `got: ${css}`)
+})
+
+test('string concatenation still works for non-dimensions', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ border: theme.border + ' red', width: 8 + 'px' }))`
+  const css = transform('test.jsx', src, DIM_THEME).cssRules[0].css
+  assert(css.includes('border:1px solid red') && css.includes('width:8px'), `got: ${css}`)
+})
+
+test('incompatible units → located build error', () => {
+  assertThrows(
+    () => transform('src/A.tsx', `import { css } from 'taikocss/css'\nconst x = css({ rotate: '1rem' + '45deg' })`),
+    /src\/A\.tsx:2:25: css\(\) — cannot evaluate `1rem \+ 45deg`: incompatible units/,
+    'incompatible units'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ padding: theme.spacing.md + 2 }))`, DIM_THEME),
+    /cannot evaluate `1rem \+ 2`: one side has a unit[\s\S]*Hint:/,
+    'unitless with dimension'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ width: '2px' * '3px' })`),
+    /cannot evaluate `2px \* 3px`: at least one side must be a unitless number/,
+    'dimension product'
+  )
+})
+
+test('arithmetic on a non-numeric string → build error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ width: 'auto' * 2 })`),
+    /multiplication is only supported between numbers and dimensions, got the string 'auto' and the number 2/,
+    'non-numeric operand'
+  )
+})
+
+console.log('\n── Operators ──────────────────────────────────────────────────')
+
+const OP_THEME = JSON.stringify({ spacing: { unit: 8, md: '1rem', gap: 'var(--gap)' }, scale: '2' })
+
+const opCss = (value, theme = OP_THEME) =>
+  transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ width: ${value} }))`, theme).cssRules[0].css
+
+test('unary minus on numbers, dimensions and var() tokens', () => {
+  assert(opCss('-theme.spacing.unit').includes('width:-8px'), `got: ${opCss('-theme.spacing.unit')}`)
+  assert(opCss('-theme.spacing.md').includes('width:-1rem'), `got: ${opCss('-theme.spacing.md')}`)
+  assert(opCss('-theme.spacing.gap').includes('width:calc(-1 * var(--gap))'), `got: ${opCss('-theme.spacing.gap')}`)
+  assert(opCss('-(theme.spacing.unit * 2)').includes('width:-16px'), `got: ${opCss('-(theme.spacing.unit * 2)')}`)
+})
+
+test('negative margin from a theme token', () => {
+  const css = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ marginTop: -theme.spacing.unit * 2 }))`, OP_THEME).cssRules[0].css
+  assert(css.includes('margin-top:-16px'), `got: ${css}`)
+})
+
+test('unary plus converts a numeric string', () => {
+  assert(opCss('+theme.scale * 4').includes('width:8px'), `got: ${opCss('+theme.scale * 4')}`)
+  assertThrows(() => opCss(`+'auto'`), /unary plus of the string 'auto' is not a number/, 'unary plus error')
+})
+
+test('+ - * / on numbers', () => {
+  assert(opCss('theme.spacing.unit + 2').includes('width:10px'), 'addition')
+  assert(opCss('theme.spacing.unit - 2').includes('width:6px'), 'subtraction')
+  assert(opCss('theme.spacing.unit * 3').includes('width:24px'), 'multiplication')
+  assert(opCss('theme.spacing.unit / 4').includes('width:2px'), 'division')
+})
+
+test('% remainder', () => {
+  assert(opCss('theme.spacing.unit % 3').includes('width:2px'), `got: ${opCss('theme.spacing.unit % 3')}`)
+  assert(opCss(`'10px' % '4px'`).includes('width:2px'), `got: ${opCss(`'10px' % '4px'`)}`)
+  assertThrows(() => opCss('theme.spacing.unit % 0'), /remainder of a division by zero/, 'remainder by zero')
+})
+
+test('** exponentiation', () => {
+  assert(opCss('2 ** 3').includes('width:8px'), `got: ${opCss('2 ** 3')}`)
+  assert(opCss('theme.spacing.unit * 1.25 ** 2').includes('width:12.5px'), `got: ${opCss('theme.spacing.unit * 1.25 ** 2')}`)
+  assertThrows(() => opCss(`theme.spacing.md ** 2`), /cannot evaluate `1rem \*\* 2`: \*\* only applies to unitless numbers/, 'dimension power')
+})
+
+test('comparisons on numbers', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({
+    width: theme.spacing.unit > 4 ? 1 : 2,
+    height: theme.spacing.unit <= 4 ? 1 : 2,
+    top: theme.spacing.unit === 8 ? 1 : 2,
+    left: theme.spacing.unit !== 8 ? 1 : 2,
+  }))`
+  const css = transform('test.jsx', src, OP_THEME).cssRules[0].css
+  for (const part of ['width:1px', 'height:2px', 'top:1px', 'left:2px']) {
+    assert(css.includes(part), `expected ${part}, got: ${css}`)
+  }
+})
+
+test('parenthesised sub-expressions in template interpolations', () => {
+  const src = 'import { css } from "taikocss/css"\nconst x = css(({ theme }) => ({ margin: `${(theme.spacing.unit + 2) * 2}px ${-(theme.spacing.unit / 2)}px` }))'
+  const css = transform('test.jsx', src, OP_THEME).cssRules[0].css
+  assert(css.includes('margin:20px -4px'), `got: ${css}`)
+})
+
+test('IEEE-safe number formatting', () => {
+  assert(opCss('0.1 + 0.2').includes('width:.3px'), `got: ${opCss('0.1 + 0.2')}`)
+  const src = 'import { css } from "taikocss/css"\nconst x = css({ margin: `${0.1 * 3}rem`, lineHeight: 0.7 + 0.1 })'
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('margin:.3rem') && css.includes('line-height:.8'), `got: ${css}`)
+})
+
+test('non-finite results → build error', () => {
+  assertThrows(() => opCss('0 ** -1'), /`0 \*\* -1` does not evaluate to a finite number/, 'infinite result')
+  assertThrows(() => opCss('theme.spacing.unit / 0'), /division by zero/, 'division by zero')
+})
+
+test('unsupported operators → build error', () => {
+  assertThrows(() => opCss('theme.spacing.unit << 1'), /unsupported binary operator/, 'shift operator')
+})
+
+console.log('\n── TypeScript wrappers ────────────────────────────────────────')
+
+test('satisfies / as on the css() argument', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const a = css({ color: 'red' } satisfies StyleObject)
+    const b = css(({ color: 'blue' }) as StyleObject)
+  `
+  const { code, cssRules } = transform('test.tsx', src)
+  assert(cssRules.length === 2, `expected 2 rules, got ${cssRules.length}`)
+  assert(!code.includes('css('), `css() calls should be replaced, got: ${code}`)
+})
+
+test('typed theme callback parameter and return wrapper', () => {
+  const theme = JSON.stringify({ colors: { primary: 'tomato' } })
+  const src = `
+    import { css } from 'taikocss/css'
+    const x = css(({ theme }: { theme: Theme }): StyleObject =>
+      ({ color: theme.colors.primary! }) satisfies StyleObject)
+  `
+  const css = transform('test.tsx', src, theme).cssRules[0].css
+  assert(css.includes('color:tomato'), `got: ${css}`)
+})
+
+test('non-null, as and <T> assertions inside values and member chains', () => {
+  const theme = JSON.stringify({ colors: { primary: 'tomato' }, spacing: { unit: 8 } })
+  const src = `
+    import { css } from 'taikocss/css'
+    const x = css(({ theme }) => ({
+      color: theme.colors!.primary,
+      padding: (theme.spacing.unit as number) * 2,
+      margin: -(theme.spacing.unit satisfies number),
+      borderColor: (theme.colors as Colors).primary,
+    }))
+  `
+  const css = transform('test.tsx', src, theme).cssRules[0].css
+  for (const part of ['color:tomato', 'padding:16px', 'margin:-8px', 'border-color:tomato']) {
+    assert(css.includes(part), `expected ${part}, got: ${css}`)
+  }
+})
+
+test('wrapped consts, nested objects and spreads', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const SIZES = { md: 16 } satisfies Record<string, number>
+    const base = { color: 'red' } as StyleObject
+    const hover = { color: 'blue' }!
+    const x = css({
+      ...(base as StyleObject),
+      fontSize: SIZES.md,
+      '&:hover': hover,
+      '&:focus': { color: 'green' } satisfies StyleObject,
+    })
+  `
+  const css = transform('test.tsx', src).cssRules[0].css
+  for (const part of ['color:red', 'font-size:16px', ':hover{color:#00f}', ':focus{color:green}']) {
+    assert(css.includes(part), `expected ${part}, got: ${css}`)
+  }
+  // Angle-bracket assertions are only valid outside .tsx
+  const legacy = transform('test.ts', `import { css } from 'taikocss/css'\nconst hover = <StyleObject>{ color: 'blue' }\nconst x = css({ '&:hover': hover })`).cssRules[0].css
+  assert(legacy.includes(':hover{color:#00f}'), `got: ${legacy}`)
+})
+
+test('keyframes binding with a type assertion', () => {
+  const src = `
+    import { css, keyframes } from 'taikocss/css'
+    const fade = keyframes\`from { opacity: 0 } to { opacity: 1 }\` as string
+    const x = css({ animation: \`\${fade} 1s\` })
+  `
+  const { keyframes: kf, cssRules } = transform('test.tsx', src)
+  assert(cssRules[0].css.includes(kf[0].name), `got: ${cssRules[0].css}`)
+})
+
+console.log('\n── Theme callback parameters ──────────────────────────────────')
+
+const CB_THEME = JSON.stringify({
+  colors: { primary: 'tomato', text: 'black' },
+  spacing: { unit: 8 },
+  typography: { heading: { fontWeight: 700, fontSize: 24 } },
+})
+
+test('plain parameter is the theme', () => {
+  const css = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css((t) => ({ color: t.colors.primary, ...t.typography.heading }))`, CB_THEME).cssRules[0].css
+  assert(css.includes('color:tomato') && css.includes('font-weight:700'), `got: ${css}`)
+})
+
+test('renamed and nested destructuring', () => {
+  const a = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css(({ theme: th }) => ({ color: th.colors.primary }))`, CB_THEME).cssRules[0].css
+  assert(a.includes('color:tomato'), `got: ${a}`)
+  const b = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css(({ theme: { colors, spacing: { unit } } }) => ({ color: colors.text, padding: unit * 2 }))`, CB_THEME).cssRules[0].css
+  assert(b.includes('color:#000') && b.includes('padding:16px'), `got: ${b}`)
+})
+
+test('function expression callback', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css(function ({ theme }) { return { color: theme.colors.primary } })`
+  const css = transform('test.jsx', src, CB_THEME).cssRules[0].css
+  assert(css.includes('color:tomato'), `got: ${css}`)
+})
+
+test('block body with local consts', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const x = css(({ theme }) => {
+      const { colors } = theme
+      const gap = theme.spacing.unit * 2
+      const accent = colors.primary
+      const hover = { color: accent }
+      return { color: colors.text, gap, padding: gap / 2, '&:hover': hover }
+    })
+  `
+  const css = transform('test.jsx', src, CB_THEME).cssRules[0].css
+  for (const part of ['color:#000', 'gap:16px', 'padding:8px', ':hover{color:tomato}']) {
+    assert(css.includes(part), `expected ${part}, got: ${css}`)
+  }
+})
+
+test('callback returning a local style object', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css((t) => { const styles = { color: t.colors.primary }; return styles })`
+  const css = transform('test.jsx', src, CB_THEME).cssRules[0].css
+  assert(css.includes('color:tomato'), `got: ${css}`)
+})
+
+test('let inside a callback is a runtime variable', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => { let c = theme.colors.primary; return { color: c } })`
+  assertThrows(() => transform('test.jsx', src, CB_THEME), /identifier 'c' is a runtime variable/, 'let error')
+})
+
+test('renamed theme parameter without a theme → build error naming it', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css((t) => ({ color: t.colors.primary }))`, null),
+    /'t' is the theme, but no theme was provided/,
+    'missing theme error'
+  )
+})
+
+console.log('\n── Computed keys ──────────────────────────────────────────────')
+
+test('media query key built from a theme token', () => {
+  const theme = JSON.stringify({ breakpoints: { md: '768px' } })
+  const src = 'import { css } from "taikocss/css"\nconst x = css(({ theme }) => ({ color: "red", [`@media (min-width: ${theme.breakpoints.md})`]: { color: "blue" } }))'
+  const css = transform('test.jsx', src, theme).cssRules[0].css
+  assert(css.includes('@media (min-width:768px){') && css.includes('{color:#00f}'), `got: ${css}`)
+})
+
+test('selector key from a local constant', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const SELECTORS = { hocus: '&:hover, &:focus-visible' }
+    const PROP = 'backgroundColor'
+    const x = css({ [SELECTORS.hocus]: { [PROP]: 'red' } })
+  `
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes(':hover') && css.includes(':focus-visible') && css.includes('background-color:red'), `got: ${css}`)
+})
+
+test('imported selector constant as a key', () => {
+  const modules = withModules('/src/Card.jsx', { './selectors': '/src/selectors.ts' }, {
+    '/src/selectors.ts': { code: `export const SELECTORS = { dark: '[data-theme="dark"] &' }`, imports: {} },
+  })
+  const src = `
+    import { css } from 'taikocss/css'
+    import { SELECTORS } from './selectors'
+    const x = css({ [SELECTORS.dark]: { color: 'white' } })
+  `
+  const css = transform('/src/Card.jsx', src, null, null, modules).cssRules[0].css
+  assert(css.includes('[data-theme=dark] .') && css.includes('color:#fff'), `got: ${css}`)
+})
+
+test('keyframe binding in a computed key', () => {
+  const src = `
+    import { css, keyframes } from 'taikocss/css'
+    const fade = keyframes\`from { opacity: 0 } to { opacity: 1 }\`
+    const x = css({ [\`--animation-\${fade}\`]: '1' })
+  `
+  const { keyframes: kf, cssRules } = transform('test.jsx', src)
+  assert(cssRules[0].css.includes(`--animation-${kf[0].name}:1`), `got: ${cssRules[0].css}`)
+})
+
+test('later computed key overrides an earlier static one', () => {
+  const src = `import { css } from 'taikocss/css'\nconst KEY = 'color'\nconst x = css({ color: 'red', [KEY]: 'blue' })`
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('color:#00f') && !css.includes('color:red'), `got: ${css}`)
+})
+
+test('runtime or non-string computed key → build error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ [getKey()]: 'red' })`),
+    /call to 'getKey'/,
+    'runtime key'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ [true]: 'red' })`),
+    /computed key 'true' evaluates to true; keys must be strings or numbers/,
+    'boolean key'
+  )
+})
+
+console.log('\n── Cross-component selectors ──────────────────────────────────')
+
+test('css() binding in a selector key resolves to its class', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const card = css({ padding: 16 })
+    const title = css({ opacity: 0.5, [\`\${card}:hover &\`]: { opacity: 1 } })
+  `
+  const { cssRules } = transform('test.jsx', src)
+  const cardClass = cssRules[0].hash
+  assert(cssRules[1].css.includes(`.cls_${cardClass}:hover .cls_${cssRules[1].hash}{opacity:1}`), `got: ${cssRules[1].css}`)
+})
+
+test('css() binding declared inside a component', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    function Card() {
+      const root = css({ padding: 16 })
+      const icon = css({ [root]: { color: 'red' }, [\`\${root}:focus-within &\`]: { color: 'blue' } })
+      return root + icon
+    }
+  `
+  const { cssRules } = transform('test.jsx', src)
+  const root = `.cls_${cssRules[0].hash}`
+  assert(cssRules[1].css.includes(`${root}:focus-within`), `got: ${cssRules[1].css}`)
+})
+
+test('css() binding declared after its use', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const title = css({ [\`\${card}:hover &\`]: { opacity: 1 } })
+    const card = css({ padding: 16 })
+  `
+  const { cssRules } = transform('test.jsx', src)
+  assert(cssRules[0].css.includes(`.cls_${cssRules[1].hash}:hover`), `got: ${cssRules[0].css}`)
+})
+
+test('a parameter shadowing a css() binding is not its class', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    function A() { const card = css({ color: 'red' }) }
+    function B(card) { return css({ [\`\${card}:hover &\`]: { opacity: 1 } }) }
+  `
+  assertThrows(() => transform('test.jsx', src), /test\.jsx:4:41: .*'card' is a runtime variable/, 'shadowing parameter')
+  const local = src.replace('function B(card) {', "function B() { const card = '.foo';")
+  assertThrows(() => transform('test.jsx', local), /'card' is a runtime variable/, 'shadowing local')
+})
+
+test('imported css() binding resolves to the class its module generates', () => {
+  const cardSrc = `import { css } from 'taikocss/css'\nexport const card = css(({ theme }) => ({ padding: theme.space }))`
+  const theme = JSON.stringify({ space: '16px' })
+  const cardHash = transform('/src/Card.jsx', cardSrc, theme).cssRules[0].hash
+  const modules = withModules('/src/Title.jsx', { './Card': '/src/Card.jsx' }, {
+    '/src/Card.jsx': { code: cardSrc, imports: {} },
+  })
+  const src = `
+    import { css } from 'taikocss/css'
+    import { card } from './Card'
+    const title = css({ [\`\${card}:hover &\`]: { opacity: 1 } })
+  `
+  const { cssRules, dependencies } = transform('/src/Title.jsx', src, theme, null, modules)
+  assert(cssRules[0].css.includes(`.cls_${cardHash}:hover`), `got: ${cssRules[0].css}`)
+  assert(dependencies.includes('/src/Card.jsx'), `deps: ${dependencies}`)
+})
+
+test('unresolvable class reference → build error', () => {
+  const modules = withModules('/src/Title.jsx', { './Card': '/src/Card.jsx' }, {
+    '/src/Card.jsx': { code: `import { css } from 'taikocss/css'\nexport const card = css(getStyles())`, imports: {} },
+  })
+  assertThrows(
+    () => transform('/src/Title.jsx', `
+      import { css } from 'taikocss/css'
+      import { card } from './Card'
+      const title = css({ [\`\${card}:hover &\`]: { opacity: 1 } })
+    `, null, null, modules),
+    /'card' refers to a css\(\) class whose styles are not static/,
+    'runtime styles'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst card = css({ [\`\${card}:hover &\`]: { opacity: 1 } })`),
+    /circular constant reference: card → card/,
+    'self reference'
+  )
+  assertThrows(
+    () => transform('test.jsx', `
+      import { css } from 'taikocss/css'
+      import { card } from './Card'
+      const title = css({ [\`\${card}:hover &\`]: { opacity: 1 } })
+    `),
+    /'card' is imported from '.\/Card', which could not be resolved/,
+    'unresolved import'
+  )
+})
+
+console.log('\n── Breakpoint shorthands ──────────────────────────────────────')
+
+const BREAKPOINTS_THEME = JSON.stringify({ breakpoints: { sm: 640, md: '768px', lg: '64rem' } })
+
+test('@md / @md-down / @between expand to media queries', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({
+    color: 'red',
+    '@md': { color: 'blue' },
+    '@md-down': { color: 'green' },
+    '@between sm lg': { padding: 4 },
+  })`
+  const css = transform('test.jsx', src, BREAKPOINTS_THEME).cssRules[0].css
+  assert(css.includes('@media (min-width:768px){'), `min-width: ${css}`)
+  assert(css.includes('@media not (min-width:768px){'), `down: ${css}`)
+  assert(css.includes('@media (min-width:640px) and (not (min-width:64rem)){'), `between: ${css}`)
+})
+
+test('breakpoint shorthand in @container queries', () => {
+  const src = `import { css, container } from 'taikocss/css'\nconst x = css({
+    ...container('sidebar', 'inline-size'),
+    '@container @md': { margin: 1 },
+    '@container sidebar @between md lg': { margin: 2 },
+  })`
+  const css = transform('test.jsx', src, BREAKPOINTS_THEME).cssRules[0].css
+  assert(css.includes('@container (width>=768px){'), `unnamed: ${css}`)
+  assert(css.includes('@container sidebar (width>=768px) and (width<64rem){'), `named: ${css}`)
+})
+
+test('nested and prelude-less at-rules are left alone', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ '&:hover': { '@md': { color: 'blue' } }, '@starting-style': { opacity: 0 } })`
+  const css = transform('test.jsx', src, BREAKPOINTS_THEME).cssRules[0].css
+  assert(css.includes('@media (min-width:768px)') && css.includes('@starting-style{'), `got: ${css}`)
+})
+
+test('unknown breakpoint or missing theme → build error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ '@xl': { color: 'red' } })`, BREAKPOINTS_THEME),
+    /'xl' in '@xl' is not a breakpoint in theme.breakpoints \(sm, md, lg\)/,
+    'unknown breakpoint'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ '@between md': { color: 'red' } })`, BREAKPOINTS_THEME),
+    /'@between md' is not a breakpoint shorthand/,
+    'malformed between'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ '@md': { color: 'red' } })`),
+    /'@md' is a breakpoint shorthand, but no theme was provided/,
+    'no theme'
+  )
+})
+
+test('taiko() rejects breakpoints that do not increase', async () => {
+  const { taiko } = await import('./plugin.js')
+  taiko({ theme: { breakpoints: { sm: 640, md: '48rem', lg: '1024px' } } })
+  assertThrows(
+    () => taiko({ theme: { breakpoints: { sm: 640, md: '30rem' } } }),
+    /theme.breakpoints must increase: md \(30rem\) is not wider than sm \(640\)/,
+    'decreasing'
+  )
+  assertThrows(
+    () => taiko({ theme: { breakpoints: { md: 'wide' } } }),
+    /theme.breakpoints.md must be a number of px or a px\/rem\/em length/,
+    'not a length'
+  )
+})
+
+console.log('\n── Responsive values ──────────────────────────────────────────')
+
+test('responsive array → base declaration plus min-width media queries', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ padding: [8, 16, null, 24], lineHeight: [1.2, 1.5] })`
+  const css = transform('test.jsx', src, BREAKPOINTS_THEME).cssRules[0].css
+  assert(/^\.cls_\w+\{padding:8px\}@media \(min-width:640px\)\{\.cls_\w+\{padding:16px\}\}@media \(min-width:64rem\)\{\.cls_\w+\{padding:24px\}\}/.test(css), `padding: ${css}`)
+  assert(css.includes('{line-height:1.2}') && css.includes('{line-height:1.5}}'), `unitless: ${css}`)
+})
+
+test('breakpoint-keyed object is sorted mobile-first', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ margin: { lg: 3, base: 1, md: 2 } })`
+  const css = transform('test.jsx', src, BREAKPOINTS_THEME).cssRules[0].css
+  const base = css.indexOf('{margin:1px}'), md = css.indexOf('(min-width:768px)'), lg = css.indexOf('(min-width:64rem)')
+  assert(base >= 0 && base < md && md < lg, `got: ${css}`)
+})
+
+test('responsive values inside nested selectors, with theme tokens', () => {
+  const theme = JSON.stringify({ breakpoints: { md: 768 }, colors: { primary: 'tomato' } })
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ '&:hover': { color: ['red', theme.colors.primary] } }))`
+  const css = transform('test.jsx', src, theme).cssRules[0].css
+  assert(css.includes(':hover{color:red}') && css.includes('@media (min-width:768px){.') && css.includes(':hover{color:tomato}'), `got: ${css}`)
+})
+
+test('objects without breakpoint keys stay nested rules', () => {
+  const css = transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ h1: { color: 'red' } })`, BREAKPOINTS_THEME).cssRules[0].css
+  assert(css.includes(' h1{color:red}'), `got: ${css}`)
+})
+
+test('too many responsive values or unknown breakpoint → build error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ padding: [1, 2, 3, 4, 5] })`, BREAKPOINTS_THEME),
+    /'padding' has 5 responsive values, but theme.breakpoints defines 3 \(sm, md, lg\)/,
+    'too many values'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ padding: [1, 2] })`),
+    /theme.breakpoints defines no breakpoints/,
+    'no breakpoints'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ padding: { base: 1, xl: 2 } })`, BREAKPOINTS_THEME),
+    /'xl' in the value of 'padding' is neither a breakpoint in theme.breakpoints \(sm, md, lg\) nor a condition/,
+    'unknown key'
+  )
+})
+
+console.log('\n── Condition shorthands ───────────────────────────────────────')
+
+test('condition keys expand to selectors and at-rules', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({
+    color: 'black',
+    _hover: { color: 'red' },
+    _focusVisible: { outline: '2px solid' },
+    _dark: { color: 'white' },
+    _rtl: { marginLeft: 4 },
+    _motionSafe: { transition: 'color 1s' },
+    _print: { display: 'none' },
+  })`
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(/\.cls_\w+:hover\{color:red\}/.test(css), `hover: ${css}`)
+  assert(/\.cls_\w+:focus-visible\{outline:2px solid\}/.test(css), `focus-visible: ${css}`)
+  assert(/\[data-mode=dark\] \.cls_\w+\{color:#fff\}/.test(css), `dark: ${css}`)
+  assert(/\[dir=rtl\] \.cls_\w+\{margin-left:4px\}/.test(css), `rtl: ${css}`)
+  assert(css.includes('@media (prefers-reduced-motion:no-preference){'), `motion: ${css}`)
+  assert(css.includes('@media print{'), `print: ${css}`)
+})
+
+test('property-level condition object, ordered base → breakpoints → conditions', () => {
+  const theme = JSON.stringify({ breakpoints: { md: 768 } })
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ color: { _dark: 'white', md: 'gray', base: 'black', _hover: 'red' } })`
+  const css = transform('test.jsx', src, theme).cssRules[0].css
+  const order = ['{color:#000}', '@media (min-width:768px)', '[data-mode=dark]', ':hover{color:red}'].map(s => css.indexOf(s))
+  assert(order.every((i, n) => i >= 0 && (n === 0 || i > order[n - 1])), `got: ${css}`)
+})
+
+test('nested condition objects inside a condition', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ _light: { color: { base: 'red', _hover: 'blue' } } })`
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(/\[data-mode=light\] \.cls_\w+:hover\{color:#00f\}/.test(css), `got: ${css}`)
+})
+
+test('theme.conditions extends and overrides the built-ins', () => {
+  const theme = JSON.stringify({ conditions: { _groupHover: '.group:hover &', _dark: '.dark &' } })
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ _groupHover: { opacity: 1 }, _dark: { color: 'white' } })`
+  const css = transform('test.jsx', src, theme).cssRules[0].css
+  assert(/\.group:hover \.cls_\w+\{opacity:1\}/.test(css), `extended: ${css}`)
+  assert(/\.dark \.cls_\w+\{color:#fff\}/.test(css) && !css.includes('data-mode'), `overridden: ${css}`)
+})
+
+test('unknown condition or malformed theme.conditions → build error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ _hovr: { color: 'red' } })`),
+    /unknown condition '_hovr'\. Known conditions: _active, .*_hover/,
+    'unknown condition'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { globalCss } from 'taikocss/css'\nglobalCss({ body: { _height: '1px' } })`),
+    /test\.jsx:2:21: css\(\) — unknown condition '_height'/,
+    'unknown condition in globalCss'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ color: 'red' })`, JSON.stringify({ conditions: { hover: '&:hover' } })),
+    /theme\.conditions — 'hover' must start with '_'/,
+    'missing underscore'
+  )
+})
+
+console.log('\n── Fallback values ────────────────────────────────────────────')
+
+const FALLBACK_IMPORT = `import { css, fallback } from 'taikocss/css'\n`
+
+test('fallback() → one declaration per value, in order', () => {
+  const css = transform('test.jsx', FALLBACK_IMPORT + `const x = css({ height: fallback('100vh', '100dvh') })`).cssRules[0].css
+  assert(/\{height:100vh;height:100dvh\}/.test(css), `got: ${css}`)
+})
+
+test('fallbacks the targets do not need are dropped by the minifier', () => {
+  // Every target supports flexbox and sticky positioning
+  const src = FALLBACK_IMPORT + `const x = css({ display: fallback('-webkit-box', 'flex'), position: fallback('-webkit-sticky', 'sticky') })`
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('display:flex') && !css.includes('-webkit-box'), `display: ${css}`)
+  assert(css.includes('position:sticky') && !css.includes('-webkit-sticky'), `position: ${css}`)
+})
+
+test('fallbacks in nested rules and behind later overrides', () => {
+  const src = FALLBACK_IMPORT + `const x = css({ '&:hover': { height: fallback('100vh', '100dvh') }, width: 10, ...{ width: fallback('100vw', '100dvw') } })`
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes(':hover{height:100vh;height:100dvh}'), `nested: ${css}`)
+  assert(css.includes('width:100vw;width:100dvw') && !css.includes('width:10px'), `override: ${css}`)
+})
+
+test('fallback() inside a responsive value', () => {
+  const theme = JSON.stringify({ breakpoints: { md: 768 } })
+  const src = FALLBACK_IMPORT + `const x = css({ height: { base: fallback('100vh', '100dvh'), md: 'auto' } })`
+  const css = transform('test.jsx', src, theme).cssRules[0].css
+  assert(/\{height:100vh;height:100dvh\}@media \(min-width:768px\)\{\.cls_\w+\{height:auto\}\}/.test(css), `got: ${css}`)
+})
+
+test('fallback() with no values throws', () => {
+  assertThrows(
+    () => transform('test.jsx', FALLBACK_IMPORT + `const x = css({ height: fallback() })`),
+    /fallback\(\) for 'height' needs at least one value/,
+    'empty fallback',
+  )
+})
+
+test('inline arrays are responsive, including strings with a null entry', () => {
+  const theme = JSON.stringify({ breakpoints: { md: 768 } })
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ height: ['100vh', null], padding: [8, '16px'] })`
+  const css = transform('test.jsx', src, theme).cssRules[0].css
+  assert(/\{height:100vh;padding:8px\}@media \(min-width:768px\)\{\.cls_\w+\{padding:16px\}\}/.test(css), `got: ${css}`)
+})
+
+test('inline array of strings is ambiguous on every property', () => {
+  const theme = JSON.stringify({ breakpoints: { md: 768 } })
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ height: ['100vh', '100dvh'] })`, theme),
+    /test\.jsx:2:25: css\(\) — 'height' is given an inline array of strings[\s\S]*Hint: write responsive values as \{ base: …, md: … \}[\s\S]*fallback\(…\)/,
+    'height fallbacks',
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ display: ['-webkit-box', 'flex'] })`),
+    /'display' is given an inline array of strings/,
+    'display fallbacks without breakpoints',
+  )
+})
+
+test('inline array on a list property is ambiguous', () => {
+  const theme = JSON.stringify({ breakpoints: { md: 768 } })
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({\n  fontFamily: ['Inter', 'sans-serif'],\n})`, theme),
+    /test\.jsx:3:\d+: .*'fontFamily' is given an inline array[\s\S]*Hint: write the list as a string or a const list/,
+    'inline font stack',
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ transition: ['opacity 1s', 'transform 1s'] })`, theme),
+    /'transition' is given an inline array/,
+    'inline transitions',
+  )
+})
+
+test('font stacks as a string, const list or responsive object', () => {
+  const theme = JSON.stringify({ breakpoints: { md: 768 } })
+  const src = `import { css } from 'taikocss/css'\nconst STACK = ['Inter', 'sans-serif']
+    const a = css({ fontFamily: 'Inter, sans-serif' })
+    const b = css({ fontFamily: STACK })
+    const c = css({ transition: ['opacity 1s', null], fontFamily: { base: 'Inter', md: 'serif' } })`
+  const [a, b, c] = transform('test.jsx', src, theme).cssRules.map(r => r.css)
+  assert(a.includes('font-family:Inter,sans-serif'), `string: ${a}`)
+  assert(b.includes('font-family:Inter,sans-serif'), `const list: ${b}`)
+  assert(c.includes('transition:opacity 1s') && /font-family:Inter[;}][\s\S]*@media \(min-width:768px\)\{\.cls_\w+\{font-family:serif\}\}/.test(c), `responsive: ${c}`)
+})
+
+console.log('\n── Composite tokens ───────────────────────────────────────────')
+
+const COMPOSITE_THEME = JSON.stringify({
+  fonts: { body: ['Inter', 'Helvetica Neue', 'sans-serif', '3D Sans'] },
+  shadows: { card: ['0 1px 2px rgba(0,0,0,.1)', [0, 4, 8, 'rgba(0,0,0,.2)']] },
+  space: { box: [0, 8] },
+  areas: { page: ['head head', 'nav main'] },
+  textStyles: {
+    heading: { lg: { fontSize: 32, fontFamily: ['Inter', 'sans-serif'], lineHeight: 1.2 } },
+    body: { fontSize: 16, '&:first-letter': { fontWeight: 700 } },
+  },
+  layerStyles: { card: { borderRadius: 8, boxShadow: ['0 1px 2px #0003', '0 4px 8px #0002'] } },
+})
+
+test('list tokens are joined per property', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({
+    fontFamily: theme.fonts.body,
+    boxShadow: theme.shadows.card,
+    margin: theme.space.box,
+    gridTemplateAreas: theme.areas.page,
+  }))`
+  const css = transform('test.jsx', src, COMPOSITE_THEME).cssRules[0].css
+  assert(css.includes('font-family:Inter,Helvetica Neue,sans-serif,"3D Sans"'), `font stack: ${css}`)
+  assert(css.includes('box-shadow:0 1px 2px #0000001a,0 4px 8px #0003'), `shadow layers: ${css}`)
+  assert(css.includes('margin:0 8px'), `space-separated: ${css}`)
+  assert(css.includes('grid-template-areas:"head head""nav main"'), `grid areas: ${css}`)
+})
+
+test('const array as a list value', () => {
+  const src = `import { css } from 'taikocss/css'\nconst STACK = ['Fira Sans', 'monospace']\nconst x = css({ fontFamily: STACK })`
+  const css = transform('test.jsx', src).cssRules[0].css
+  assert(css.includes('font-family:Fira Sans,monospace'), `got: ${css}`)
+})
+
+test('textStyle / layerStyle expand a composite token, later keys win', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css({ textStyle: 'heading.lg', fontSize: 20, layerStyle: 'card' })`
+  const css = transform('test.jsx', src, COMPOSITE_THEME).cssRules[0].css
+  assert(css.includes('font-size:20px') && !css.includes('font-size:32px'), `override: ${css}`)
+  assert(css.includes('line-height:1.2') && css.includes('font-family:Inter,sans-serif'), `text style: ${css}`)
+  assert(css.includes('border-radius:8px') && css.includes('box-shadow:0 1px 2px #0003,0 4px 8px #0002'), `layer style: ${css}`)
+})
+
+test('spread composite token with nested selectors', () => {
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ ...theme.textStyles.body, color: 'red' }))`
+  const css = transform('test.jsx', src, COMPOSITE_THEME).cssRules[0].css
+  assert(css.includes('font-size:16px') && css.includes(':first-letter{font-weight:700}'), `got: ${css}`)
+})
+
+test('composite token misuse → build error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ textStyle: 'heading' })`, COMPOSITE_THEME),
+    /theme\.textStyles\.heading groups several tokens \(lg\); name one, e\.g\. textStyle: 'heading\.lg'/,
+    'group instead of token'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ textStyle: 'display' })`, COMPOSITE_THEME),
+    /theme\.textStyles\.display does not exist/,
+    'unknown token'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ font: theme.textStyles.body }))`, COMPOSITE_THEME),
+    /'theme\.textStyles\.body' is not a string, number or list[\s\S]*spread a composite token \(`\.\.\.theme\.textStyles\.body`\)/,
+    'object as a value'
+  )
+})
+
+console.log('\n── Keyframes objects ──────────────────────────────────────────')
+
+const KF_THEME = JSON.stringify({ colors: { primary: '#3366ff' }, spacing: { unit: 8 } })
+
+test('keyframes({ ... }) — frames serialized like css() objects', () => {
+  const src = `import { keyframes } from 'taikocss/css'\nconst fadeIn = keyframes({ from: { opacity: 0, top: 8 }, '50%': { opacity: 0.5 }, to: { opacity: 1 } })`
+  const { code, keyframes } = transform('test.jsx', src)
+  assert(keyframes.length === 1, `expected 1 keyframe rule, got ${keyframes.length}`)
+  const { name, css } = keyframes[0]
+  assert(css === `@keyframes ${name}{0%{opacity:0;top:8px}50%{opacity:.5}to{opacity:1}}`, `got: ${css}`)
+  assert(code.includes(`const fadeIn = "${name}"`), `got: ${code}`)
+})
+
+test('keyframes theme callback and theme interpolations', () => {
+  const src = `
+    import { keyframes } from 'taikocss/css'
+    const slide = keyframes(({ theme }) => ({ from: { color: theme.colors.primary, left: -theme.spacing.unit } }))
+    const flash = keyframes\`from { color: \${theme.colors.primary}; } to { color: \${({ theme }) => theme.colors.primary}; }\`
+  `
+  const { keyframes } = transform('test.jsx', src, KF_THEME)
+  assert(keyframes[0].css.includes('0%{color:#36f;left:-8px}'), `object form: ${keyframes[0].css}`)
+  assert(keyframes[1].css.includes('0%{color:#36f}to{color:#36f}'), `template form: ${keyframes[1].css}`)
+})
+
+test('keyframes binding as a bare animationName value', () => {
+  const src = `
+    import { css, keyframes } from 'taikocss/css'
+    const fadeIn = keyframes({ from: { opacity: 0 }, to: { opacity: 1 } })
+    function Toast() {
+      const pop = keyframes\`from { scale: 0.9 } to { scale: 1 }\`
+      return css({ animationName: pop })
+    }
+    const x = css({ animationName: fadeIn })
+  `
+  const { cssRules, keyframes } = transform('test.jsx', src)
+  assert(cssRules[0].css.includes(`animation-name:${keyframes[1].name}`), `function-local: ${cssRules[0].css}`)
+  assert(cssRules[1].css.includes(`animation-name:${keyframes[0].name}`), `module-level: ${cssRules[1].css}`)
+})
+
+test('a parameter shadowing a keyframes binding is not its animation', () => {
+  const src = `
+    import { css, keyframes } from 'taikocss/css'
+    function A() { const fade = keyframes({ from: { opacity: 0 } }) }
+    function B(fade) { return css({ animationName: fade }) }
+  `
+  assertThrows(() => transform('test.jsx', src), /test\.jsx:4:52: .*'fade' is a runtime variable/, 'shadowing parameter')
+})
+
+test('imported keyframes() object binding names the same animation', () => {
+  const animations = `
+    import { keyframes } from 'taikocss/css'
+    export const fadeIn = keyframes({ from: { opacity: 0 }, to: { opacity: 1 } })
+  `
+  const { keyframes } = transform('/src/animations.js', animations)
+  const modules = withModules('/src/Toast.jsx', { './animations': '/src/animations.js' }, {
+    '/src/animations.js': { code: animations, imports: { 'taikocss/css': 'taikocss/css' } },
+  })
+  const src = `import { css } from 'taikocss/css'\nimport { fadeIn } from './animations'\nconst x = css({ animation: \`\${fadeIn} 1s\`, animationName: fadeIn })`
+  const { cssRules } = transform('/src/Toast.jsx', src, null, null, modules)
+  assert(cssRules[0].css.includes(keyframes[0].name), `expected ${keyframes[0].name}, got: ${cssRules[0].css}`)
+})
+
+test('keyframes interpolation of a runtime value — error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { keyframes } from 'taikocss/css'\nlet x = 1\nconst k = keyframes\`from { opacity: \${x} }\``),
+    /'x' is declared with `let`/,
+    'let binding'
+  )
+})
+
+console.log('\n── globalCss objects ──────────────────────────────────────────')
+
+test('globalCss({ ... }) — top-level keys are selectors and at-rules', () => {
+  const src = `import { globalCss } from 'taikocss/css'\nglobalCss({
+    'html, body': { margin: 0, lineHeight: 1.5 },
+    ':root': { '--radius': '4px' },
+    '@font-face': { fontFamily: 'Inter', src: 'url(/inter.woff2)' },
+  })`
+  const { code, globalCss, cssRules } = transform('test.jsx', src)
+  assert(globalCss.length === 1 && cssRules.length === 0, `expected 1 global rule, got ${globalCss.length}`)
+  assert(globalCss[0].css ===
+    'html,body{margin:0;line-height:1.5}:root{--radius:4px}@font-face{font-family:Inter;src:url(/inter.woff2)}',
+    `got: ${globalCss[0].css}`)
+  assert(code.trim() === 'undefined', `expected the call replaced with undefined, got: ${code}`)
+})
+
+test('globalCss theme callback with nesting and breakpoint keys', () => {
+  const theme = JSON.stringify({ colors: { primary: '#3366ff' }, spacing: { unit: 8 }, breakpoints: { md: 768 } })
+  const src = `import { globalCss } from 'taikocss/css'\nglobalCss(({ theme }) => ({
+    body: { padding: theme.spacing.unit * 2, a: { '&:hover': { color: theme.colors.primary } } },
+    '@md': { body: { padding: theme.spacing.unit * 4 } },
+  }))`
+  const css = transform('test.jsx', src, theme).globalCss[0].css
+  assert(css.includes('body{padding:16px}'), `body: ${css}`)
+  assert(css.includes('body a:hover{color:#36f}'), `nested: ${css}`)
+  assert(css.includes('@media (min-width:768px){body{padding:32px}}'), `breakpoint: ${css}`)
+})
+
+test('globalCss selectors with condition keys stay rules for the selector', () => {
+  const src = `
+    import { globalCss } from 'taikocss/css'
+    globalCss({
+      body: { _dark: { background: 'black' } },
+      a: { color: 'blue', _hover: { color: 'red' } },
+    })
+  `
+  const css = transform('test.jsx', src).globalCss[0].css
+  assert(/\[data-mode=dark\] (body|:is\(body\))\{background:#000\}/.test(css), `_dark: ${css}`)
+  assert(css.includes('a{color:#00f}a:hover{color:red}'), `_hover: ${css}`)
+  assert(!css.includes(':scope'), `got: ${css}`)
+})
+
+test('globalCss with a const rules object', () => {
+  const src = `import { globalCss } from 'taikocss/css'\nconst reset = { '*, *::before': { boxSizing: 'border-box' } }\nglobalCss(reset)`
+  const { globalCss } = transform('test.jsx', src)
+  assert(globalCss[0].css.includes('box-sizing:border-box'), `got: ${globalCss[0].css}`)
+})
+
+test('globalCss object with a top-level declaration — error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { globalCss } from 'taikocss/css'\nglobalCss({ color: 'red' })`),
+    /'color' is a declaration at the top level[\s\S]*body: \{ color: 'red' \}/,
+    'top-level declaration'
+  )
+})
+
+console.log('\n── css tagged template ────────────────────────────────────────')
+
+test('css`...` — declarations and nested rules scoped to a hashed class', () => {
+  const src = 'import { css } from "taikocss/css"\nconst button = css`color: red; &:hover { color: blue; } @media (min-width: 600px) { color: green; }`'
+  const { code, cssRules } = transform('test.jsx', src)
+  assert(cssRules.length === 1, `expected 1 css rule, got ${cssRules.length}`)
+  const cls = cssRules[0].css.match(/^\.(cls_[0-9a-f]+)/)?.[1]
+  assert(cls, `expected a cls_ class, got: ${cssRules[0].css}`)
+  assert(cssRules[0].css ===
+    `.${cls}{color:red}.${cls}:hover{color:#00f}@media (min-width:600px){.${cls}{color:green}}`,
+    `got: ${cssRules[0].css}`)
+  assert(code.includes(`const button = "${cls}"`), `got: ${code}`)
+})
+
+test('css`...` interpolates theme tokens, callbacks and keyframes', () => {
+  const theme = JSON.stringify({ colors: { primary: '#3366ff' }, spacing: { unit: 8 } })
+  const src = `
+    import { css, keyframes } from 'taikocss/css'
+    const pulse = keyframes\`from { opacity: 0.5 }\`
+    const x = css\`
+      color: \${theme.colors.primary};
+      padding: \${({ theme }) => theme.spacing.unit * 2}px;
+      animation: \${pulse} 1s;
+    \`
+  `
+  const { cssRules, keyframes } = transform('test.jsx', src, theme)
+  const css = cssRules[0].css
+  assert(css.includes('color:#36f'), `theme token: ${css}`)
+  assert(css.includes('padding:16px'), `theme callback: ${css}`)
+  assert(css.includes(keyframes[0].name), `keyframes: ${css}`)
+})
+
+test('css`...` binding as a selector, locally and from another module', () => {
+  const card = "import { css } from 'taikocss/css'\nexport const card = css`color: red;`"
+  const cardClass = transform('/src/Card.jsx', card).cssRules[0].css.match(/^\.(cls_[0-9a-f]+)/)[1]
+  const modules = withModules('/src/Title.jsx', { './Card': '/src/Card.jsx' }, {
+    '/src/Card.jsx': { code: card, imports: { 'taikocss/css': 'taikocss/css' } },
+  })
+  const src = "import { css } from 'taikocss/css'\nimport { card } from './Card'\nconst title = css`${card}:hover & { opacity: 1; }`"
+  const { cssRules } = transform('/src/Title.jsx', src, null, null, modules)
+  assert(cssRules[0].css.startsWith(`.${cardClass}:hover .cls_`), `imported: ${cssRules[0].css}`)
+
+  const local = transform('test.jsx', 'import { css } from "taikocss/css"\nconst a = css`color: red;`\nconst b = css({ [`${a} &`]: { color: "blue" } })')
+  assert(local.cssRules[1].css.startsWith(`.${cardClass} .cls_`), `local: ${local.cssRules[1].css}`)
+})
+
+test('css`...` with a runtime interpolation — error', () => {
+  assertThrows(
+    () => transform('test.jsx', 'import { css } from "taikocss/css"\nfunction F(color) { return css`color: ${color};` }'),
+    /only static values are supported/,
+    'runtime variable'
+  )
+})
+
+console.log('\n── Style composition ──────────────────────────────────────────')
+
+test('css(a, b, c) — deep-merged into one class, later arguments win', () => {
+  const src = `
+    import { css } from 'taikocss/css'
+    const base = { color: 'red', padding: 8, '&:hover': { color: 'blue', opacity: 1 } }
+    const variant = { '&:hover': { color: 'green' }, margin: 4 }
+    const x = css(base, variant, { color: 'black' })
+  `
+  const { cssRules } = transform('test.jsx', src)
+  assert(cssRules.length === 1, `expected 1 css rule, got ${cssRules.length}`)
+  const css = cssRules[0].css
+  assert(/^\.cls_\w+\{color:#000;padding:8px/.test(css), `later color wins in place: ${css}`)
+  assert(/:hover\{color:green;opacity:1\}/.test(css), `nested rules merged: ${css}`)
+  assert(css.includes('margin:4px'), `variant declaration: ${css}`)
+})
+
+test('single-argument css() keeps its class name', () => {
+  const styles = `{ color: 'red', '&:hover': { color: 'blue' } }`
+  const a = transform('a.jsx', `import { css } from 'taikocss/css'\nconst x = css(${styles})`).cssRules[0].css
+  const b = transform('b.jsx', `import { css } from 'taikocss/css'\nconst x = css([${styles}, false])`).cssRules[0].css
+  assert(a === b, `expected identical rules, got: ${a} / ${b}`)
+})
+
+test('css([...]) with static conditions, callbacks and css() classes', () => {
+  const theme = JSON.stringify({ mode: 'dark', colors: { primary: '#3366ff' } })
+  const src = `
+    import { css } from 'taikocss/css'
+    const dense = true
+    const button = css({ padding: 8, color: 'black' })
+    const x = css([
+      button,
+      dense && { padding: 4 },
+      theme.mode === 'light' && { background: 'white' },
+      ({ theme }) => ({ color: theme.colors.primary }),
+      null,
+    ])
+  `
+  const { cssRules } = transform('test.jsx', src, theme)
+  const css = cssRules[1].css
+  assert(css.includes('padding:4px') && css.includes('color:#36f'), `got: ${css}`)
+  assert(!css.includes('padding:8px') && !css.includes('background'), `got: ${css}`)
+})
+
+test('css() composes an imported style object and css() class', () => {
+  const modules = withModules('/src/Button.jsx', { './styles': '/src/styles.js' }, {
+    '/src/styles.js': {
+      code: `import { css } from 'taikocss/css'
+        export const reset = { margin: 0, '&:focus': { outline: 'none' } }
+        export const card = css({ padding: 16, '&:focus': { boxShadow: '0 0 0 2px blue' } })`,
+      imports: { 'taikocss/css': 'taikocss/css' },
+    },
+  })
+  const src = `import { css } from 'taikocss/css'\nimport { reset, card } from './styles'\nconst x = css(reset, card)`
+  const css = transform('/src/Button.jsx', src, null, null, modules).cssRules[0].css
+  assert(css.includes('margin:0') && css.includes('padding:16px'), `declarations: ${css}`)
+  assert(css.includes(':focus{outline:none;box-shadow:0 0 0 2px #00f}'), `merged :focus: ${css}`)
+})
+
+test('css() with a runtime condition — error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nfunction F({ on }) { return css({ padding: 8 }, on && { padding: 4 }) }`),
+    /only static values are supported/,
+    'runtime condition'
+  )
+})
+
+test('static cx() collapses into one merged class', () => {
+  const src = `
+    import { css, cx } from 'taikocss/css'
+    const button = css({ color: 'red', padding: 8 })
+    const primary = css({ color: 'blue' })
+    const submit = cx(button, primary)
+  `
+  const { code, cssRules } = transform('test.jsx', src)
+  assert(cssRules.length === 3, `expected 3 css rules, got ${cssRules.length}`)
+  const merged = cssRules[2].css
+  assert(/^\.(cls_\w+)\.\1\{color:#00f;padding:8px\}$/.test(merged), `got: ${merged}`)
+  const cls = merged.match(/^\.(cls_\w+)/)[1]
+  const [button, primary] = cssRules.map(rule => `cls_${rule.hash}`)
+  assert(code.includes(`const submit = "${button} ${primary} ${cls}"`), `got: ${code}`)
+  assert(!code.includes('taikocss/css'), `import should be removed, got: ${code}`)
+})
+
+test('collapsed cx() keeps the classes selectors refer to', () => {
+  const src = `
+    import { css, cx } from 'taikocss/css'
+    const card = css({ padding: 8 })
+    const big = css({ padding: 16 })
+    const title = css({ [\`\${card}:hover &\`]: { opacity: 1 } })
+    const hero = cx(card, big)
+    const plain = cx({ color: 'red' }, false && big)
+  `
+  const { code, cssRules } = transform('test.jsx', src)
+  const [card, big, , merged, plain] = cssRules
+  assert(code.includes(`const hero = "cls_${card.hash} cls_${big.hash} cls_${merged.hash}"`), `got: ${code}`)
+  assert(merged.css === `.cls_${merged.hash}.cls_${merged.hash}{padding:16px}`, `merged: ${merged.css}`)
+  // Only style objects: nothing to keep, and no tier to outrank
+  assert(code.includes(`const plain = "cls_${plain.hash}"`) && plain.css === `.cls_${plain.hash}{color:red}`, `plain: ${code}`)
+})
+
+test('cx() errors in static arguments are reported', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css, cx } from 'taikocss/css'\nconst a = css({ color: 'red' })\nconst x = cx(a, { color: theme.nope })`),
+    /test\.jsx:3:/,
+    'static argument error'
+  )
+})
+
+test('cx() with a runtime condition is left to the runtime', () => {
+  const src = `
+    import { css, cx } from 'taikocss/css'
+    const button = css({ color: 'red' })
+    const active = css({ color: 'blue' })
+    export const Tab = ({ on }) => cx(button, on && active)
+  `
+  const { code, cssRules } = transform('test.jsx', src)
+  assert(cssRules.length === 2, `expected 2 css rules, got ${cssRules.length}`)
+  assert(code.includes('cx(button, on && active)'), `got: ${code}`)
+  assert(code.includes("import { cx } from 'taikocss/css'"), `cx import kept, got: ${code}`)
+})
+
+test('runtime shim cx() joins class names', async () => {
+  const { cx } = await import('./src/css.js')
+  assert(cx('a', false, null, 'b', undefined, '') === 'a b', `got: ${cx('a', false, null, 'b')}`)
+})
+
+test('a css() argument that is not static → located build error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css(\n  { color: 'red' },\n  extra,\n)`),
+    /test\.jsx:4:3: css\(\) — 'extra' is not a static style[\s\S]*Hint:/,
+    'unresolved identifier'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst base = { color: 'red' }\nfunction C(props) { return css(base, props.variant) }`),
+    /test\.jsx:3:38: css\(\) — 'props\.variant' is not a static style/,
+    'runtime member'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { css } from 'taikocss/css'\nconst x = css([{ color: 'red' }, getStyles()])`),
+    /'getStyles\(\)' is not a static style/,
+    'array entry'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { keyframes } from 'taikocss/css'\nconst k = keyframes(frames)`),
+    /keyframes\(\) — 'frames' is not a static style/,
+    'keyframes'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { globalCss } from 'taikocss/css'\nglobalCss(rules)`),
+    /globalCss\(\) — 'rules' is not a static style/,
+    'globalCss'
+  )
+})
+
+console.log('\n── Recipes ────────────────────────────────────────────────────')
+
+const RECIPE_SRC = `
+  import { recipe } from 'taikocss/css'
+  export const button = recipe(({ theme }) => ({
+    base: { padding: 8 },
+    variants: {
+      size: { sm: { fontSize: 12 }, lg: { fontSize: 18 } },
+      tone: { primary: { color: theme.colors.primary }, ghost: {} },
+      disabled: { true: { opacity: 0.5 } },
+    },
+    compoundVariants: [{ size: 'lg', tone: ['primary'], css: { fontWeight: 700 } }],
+    defaultVariants: { size: 'sm', tone: 'primary' },
+  }))
+`
+const RECIPE_THEME = JSON.stringify({ colors: { primary: '#3366ff' } })
+
+// Evaluate the generated selector function
+function recipeFn(code) {
+  return new Function(`return ${code.slice(code.indexOf('/*#__PURE__*/'))}`)()
+}
+
+test('recipe() — one class per base, variant value and compound variant', () => {
+  const { code, cssRules } = transform('test.jsx', RECIPE_SRC, RECIPE_THEME)
+  const rules = cssRules.map(r => r.css.replace(/cls_\w+/g, 'c'))
+  assert(JSON.stringify(rules) === JSON.stringify([
+    '.c{padding:8px}', '.c.c{font-size:12px}', '.c.c{font-size:18px}', '.c.c{color:#36f}', '.c.c{opacity:.5}',
+    '.c.c.c{font-weight:700}',
+  ]), `got: ${JSON.stringify(rules)}`)
+  assert(code.includes('export const button = /*#__PURE__*/'), `got: ${code}`)
+  assert(!code.includes('taikocss/css'), `import should be removed, got: ${code}`)
+})
+
+test('recipe() selector picks classes for props and defaults', () => {
+  const { code, cssRules } = transform('test.jsx', RECIPE_SRC, RECIPE_THEME)
+  const [base, sm, lg, primary, disabled, compound] = cssRules.map(r => r.css.match(/^\.(cls_\w+)/)[1])
+  const button = recipeFn(code)
+  const classes = (props) => button(props).split(' ').sort().join(' ')
+  const expect = (...names) => names.sort().join(' ')
+  assert(classes() === expect(base, sm, primary), `defaults: ${button()}`)
+  assert(classes({ size: 'lg' }) === expect(base, lg, primary, compound), `compound: ${button({ size: 'lg' })}`)
+  assert(classes({ size: 'lg', tone: 'ghost', disabled: true }) === expect(base, lg, disabled),
+    `ghost + disabled: ${button({ size: 'lg', tone: 'ghost', disabled: true })}`)
+})
+
+test('recipe() precedence holds across files and deduplicated rules', () => {
+  // Another file's base has the same styles as this recipe's variant; the
+  // variant still gets its own, more specific class, whichever loads first
+  const other = transform('/src/Badge.jsx', `
+    import { recipe } from 'taikocss/css'
+    export const badge = recipe({ base: { fontSize: 18 }, variants: { tone: { loud: { fontWeight: 700 } } } })
+  `).cssRules
+  const { code, cssRules } = transform('/src/Button.jsx', RECIPE_SRC, RECIPE_THEME)
+  const sheet = [...new Map([...other, ...cssRules].map(r => [r.hash, r.css])).values()].join('')
+  const lg = cssRules[2]
+  assert(lg.hash !== other[0].hash, `variant should not share the base class: ${lg.hash}`)
+  assert(lg.css === `.cls_${lg.hash}.cls_${lg.hash}{font-size:18px}`, `variant: ${lg.css}`)
+  assert(other[1].css.startsWith(`.cls_${other[1].hash}.cls_${other[1].hash}{`), `other variant: ${other[1].css}`)
+  assert(sheet.includes(`.cls_${other[0].hash}{font-size:18px}`), `other base kept: ${sheet}`)
+  assert(recipeFn(code)({ size: 'lg' }).split(' ').includes(`cls_${lg.hash}`), 'lg selected')
+})
+
+test('recipe() emits TypeScript types for the variant props', () => {
+  const { recipes } = transform('test.jsx', RECIPE_SRC, RECIPE_THEME)
+  assert(recipes.length === 1 && recipes[0].name === 'button', `got: ${JSON.stringify(recipes)}`)
+  assert(recipes[0].types ===
+    'export type ButtonVariants = {\n  size?: "sm" | "lg"\n  tone?: "primary" | "ghost"\n  disabled?: boolean\n}\n',
+    `got: ${recipes[0].types}`)
+})
+
+test('recipe() with an unknown variant or value — error', () => {
+  assertThrows(
+    () => transform('test.jsx', `import { recipe } from 'taikocss/css'\nconst b = recipe({ variants: { size: { sm: {} } }, defaultVariants: { size: 'md' } })`),
+    /'md' is not a value of variant 'size'\. Values: sm\./,
+    'unknown default'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { recipe } from 'taikocss/css'\nconst b = recipe({ variants: { size: { sm: {} } }, compoundVariants: [{ tone: 'x', css: {} }] })`),
+    /'tone' is not a variant\. Variants: size\./,
+    'unknown compound variant'
+  )
+  assertThrows(
+    () => transform('test.jsx', `import { recipe } from 'taikocss/css'\nconst b = recipe({ varients: {} })`),
+    /unknown key 'varients'/,
+    'unknown config key'
+  )
+})
+
+// ─── v4: Distribution layer ──────────────────────────────────────────────────
+
+console.log('\n── v4: loader ─────────────────────────────────────────────────')
+
+test('loader.cjs loads without error and exposes transform', () => {
+  const binding = require('./loader.cjs')
+  assert(typeof binding.transform === 'function', `transform should be a function, got: ${typeof binding.transform}`)
+})
+
+test('transform from loader.cjs produces a valid result', () => {
+  const { transform: t } = require('./loader.cjs')
+  const { code, cssRules } = t('test.jsx', `import { css } from 'taikocss/css'\nconst x = css({ color: 'red' })`)
+  assert(typeof code === 'string', 'code should be a string')
+  assert(Array.isArray(cssRules), 'cssRules should be an array')
+  assert(cssRules.length === 1, `expected 1 rule, got ${cssRules.length}`)
+})
+
+console.log('\n── v4: plugin.js ──────────────────────────────────────────────')
+
+test('taiko() returns a plugin object with the required hooks', async () => {
+  const { taiko } = await import('./plugin.js')
+  const plugin = taiko()
+  assert(typeof plugin.name === 'string', 'plugin.name should be a string')
+  assert(plugin.enforce === 'pre', 'plugin.enforce should be "pre"')
+  assert(typeof plugin.transform === 'function', 'plugin.transform should be a function')
+  assert(typeof plugin.resolveId === 'function', 'plugin.resolveId should be a function')
+  assert(typeof plugin.load === 'function', 'plugin.load should be a function')
+  assert(typeof plugin.handleHotUpdate === 'function', 'plugin.handleHotUpdate should be a function')
+  assert(typeof plugin.buildStart === 'function', 'plugin.buildStart should be a function')
+})
+
+test('taiko() with no arguments does not throw', async () => {
+  const { taiko } = await import('./plugin.js')
+  let threw = false
+  try { taiko() } catch { threw = true }
+  assert(!threw, 'taiko() with no args should not throw')
+})
+
+test('taiko({ theme }) passes theme through to transform — colour resolved in output', async () => {
+  const { taiko } = await import('./plugin.js')
+  const theme = { colors: { primary: 'tomato' } }
+  const plugin = taiko({ theme })
+  const src = `import { css } from 'taikocss/css'\nconst x = css(({ theme }) => ({ color: theme.colors.primary }))`
+  // Call the transform hook directly (simulate Vite)
+  const result = plugin.transform(src, 'test.jsx')
+  assert(result !== null && result !== undefined, 'expected a transform result, got null/undefined')
+  assert(typeof result.code === 'string', 'result.code should be a string')
+  assert(!result.code.includes('css('), 'css() call should be replaced')
+  assert(result.code.includes('"cls_'), 'should contain a cls_ class name')
+})
+
+test('taiko() resolves imported tokens through this.resolve and watches them', async () => {
+  const { taiko } = await import('./plugin.js')
+  const { mkdtempSync, writeFileSync } = await import('node:fs')
+  const { tmpdir } = await import('node:os')
+  const { join, dirname } = await import('node:path')
+  const root = mkdtempSync(join(tmpdir(), 'taiko-'))
+  writeFileSync(join(root, 'tokens.js'), `export const brand = 'teal'`)
+  const file = join(root, 'Button.jsx')
+  const src = `
+    import { css } from 'taikocss/css'
+    import { brand } from './tokens'
+    const x = css({ color: brand })
+  `
+  const watched = []
+  const ctx = {
+    resolve: async (spec, importer) => ({ id: join(dirname(importer), `${spec}.js`) }),
+    addWatchFile: (dep) => watched.push(dep),
+    error: (msg) => { throw new Error(msg) },
+  }
+  const result = await taiko().transform.call(ctx, src, file)
+  assert(result && result.code.includes('"cls_'), `expected extracted class, got: ${result?.code}`)
+  assert(watched.includes(join(root, 'tokens.js')), `expected tokens.js to be watched, got: ${watched}`)
+})
+
+test('taiko() caches imported modules until they change', async () => {
+  const { taiko } = await import('./plugin.js')
+  const { mkdtempSync, writeFileSync, utimesSync } = await import('node:fs')
+  const { tmpdir } = await import('node:os')
+  const { join, dirname } = await import('node:path')
+  const root = mkdtempSync(join(tmpdir(), 'taiko-'))
+  const tokens = join(root, 'tokens.js')
+  writeFileSync(tokens, `import { base } from './base'\nexport const brand = base`)
+  writeFileSync(join(root, 'base.js'), `export const base = 'teal'`)
+  const src = `
+    import { css } from 'taikocss/css'
+    import { brand } from './tokens'
+    const x = css({ color: brand })
+  `
+  const resolved = []
+  const ctx = {
+    resolve: async (spec, importer) => {
+      resolved.push(`${importer} → ${spec}`)
+      return { id: join(dirname(importer), `${spec}.js`) }
+    },
+    error: (msg) => { throw new Error(msg) },
+  }
+  const plugin = taiko()
+  const css = async () => {
+    const result = await plugin.transform.call(ctx, src, join(root, 'Button.jsx'))
+    return plugin.load('\0' + result.code.match(/import "(virtual:taikocss\/[^"]+)"/)[1])
+  }
+  assert((await css()).includes('color:teal'), 'first transform')
+  assert(resolved.length === 2, `first walk resolves every import: ${resolved}`)
+  assert((await css()).includes('color:teal') && resolved.length === 3, `unchanged modules are cached: ${resolved}`)
+  writeFileSync(tokens, `export const brand = 'navy'`)
+  utimesSync(tokens, new Date(), new Date(Date.now() + 5000))
+  assert((await css()).includes('color:navy'), 'a changed module is re-read')
+})
+
+test('taiko() reports an import graph over maxModules', async () => {
+  const { taiko } = await import('./plugin.js')
+  const { mkdtempSync, writeFileSync } = await import('node:fs')
+  const { tmpdir } = await import('node:os')
+  const { join, dirname } = await import('node:path')
+  const root = mkdtempSync(join(tmpdir(), 'taiko-'))
+  for (let i = 0; i < 3; i++) writeFileSync(join(root, `m${i}.js`), `import './m${i + 1}'\nexport const v = ${i}`)
+  writeFileSync(join(root, 'm3.js'), `export const v = 3`)
+  const ctx = {
+    resolve: async (spec, importer) => ({ id: join(dirname(importer), `${spec.slice(2)}.js`) }),
+    error: (msg) => { throw new Error(msg) },
+  }
+  const src = `import { css } from 'taikocss/css'\nimport { v } from './m0'\nconst x = css({ zIndex: v })`
+  const file = join(root, 'App.jsx')
+  assert(await taiko({ maxModules: 5 }).transform.call(ctx, src, file), 'within the limit')
+  let message = ''
+  try {
+    await taiko({ maxModules: 3 }).transform.call(ctx, src, file)
+  } catch (e) {
+    message = e.message
+  }
+  assert(/the imports of .*App\.jsx reach more than 3 local modules[\s\S]*maxModules/.test(message), `got: ${message}`)
+})
+
+test('taiko({ defines }) and Vite env feed build-time constants', async () => {
+  const { taiko } = await import('./plugin.js')
+  const plugin = taiko({ defines: { 'process.env.BRAND': '"teal"' } })
+  plugin.configResolved({ define: { __FLAG__: 'true', __RAW__: 'window.x' }, env: { MODE: 'production' }, isProduction: true })
+  const src = `import { css } from 'taikocss/css'
+  const x = css({
+    color: process.env.BRAND,
+    outline: process.env.NODE_ENV === 'production' && __FLAG__ ? 'none' : '1px solid red',
+    content: import.meta.env.MODE === 'production' ? '"p"' : '"d"',
+  })`
+  const result = plugin.transform(src, 'test.jsx')
+  const vid = result.code.match(/import "(virtual:taikocss\/[^"]+)"/)[1]
+  const css = plugin.load('\0' + vid)
+  assert(css.includes('color:teal') && css.includes('outline:none') && css.includes('"p"'), `got: ${css}`)
+})
+
+test('rustCssPlugin export is a valid plugin object', async () => {
+  const { rustCssPlugin } = await import('./plugin.js')
+  assert(typeof rustCssPlugin === 'object' && rustCssPlugin !== null, 'rustCssPlugin should be an object')
+  assert(typeof rustCssPlugin.name === 'string', 'rustCssPlugin.name should be a string')
+  assert(typeof rustCssPlugin.transform === 'function', 'rustCssPlugin.transform should be a function')
+})
+
+console.log('\n── v4: css shim (src/css.ts compiled) ─────────────────────────')
+
+test('src/css.ts shim — css() returns an empty string', async () => {
+  // Import the compiled JS shim (tsc must have run, or we test the TS source via tsx/ts-node)
+  // We test the contract: the shim must return '' and not throw.
+  // Dynamic import of a .ts file only works if tsx/ts-node is present;
+  // guard gracefully if not available.
+  let cssShim
+  try {
+    cssShim = await import('./src/css.ts')
+  } catch {
+    try {
+      cssShim = await import('./src/css.js')
+    } catch {
+      console.log('    (skipped — shim not compiled yet; run tsc first)')
+      return
+    }
+  }
+  assert(typeof cssShim.css === 'function', 'css should be a function')
+  assert(cssShim.css({ color: 'red' }) === '', 'css() shim should return ""')
+  assert(typeof cssShim.globalCss === 'function', 'globalCss should be a function')
+  assert(typeof cssShim.keyframes === 'function', 'keyframes should be a function')
+  assert(typeof cssShim.container === 'function', 'container should be a function')
+  assert(cssShim.keyframes`from{opacity:0}to{opacity:1}` === '', 'keyframes shim should return ""')
+  const containerResult = cssShim.container('sidebar', 'inline-size')
+  assert(typeof containerResult === 'object', 'container() shim should return an object')
+})
+
+test('src/css.ts shim — helpers match the build-time output', async () => {
+  const cssShim = await import('./src/css.js')
+  assert(cssShim.rem(14) === '0.875rem', `rem(14) → ${cssShim.rem(14)}`)
+  assert(cssShim.em(1, 20) === '0.05em', `em(1, 20) → ${cssShim.em(1, 20)}`)
+  assert(cssShim.px(3) === '3px', `px(3) → ${cssShim.px(3)}`)
+  assert(cssShim.fluid(14, 20) === 'clamp(0.875rem, 0.75rem + 0.625vw, 1.25rem)', `fluid(14, 20) → ${cssShim.fluid(14, 20)}`)
+})
+
+test('src/css.ts shim — colour functions return CSS colour functions', async () => {
+  const cssShim = await import('./src/css.js')
+  assert(cssShim.alpha('red', 0.5) === 'color-mix(in srgb, red 50%, transparent)', `alpha → ${cssShim.alpha('red', 0.5)}`)
+  assert(cssShim.darken('red', 0.1) === 'oklch(from red calc(l - 0.1) c h)', `darken → ${cssShim.darken('red', 0.1)}`)
+  assert(cssShim.mix('red', 'blue') === 'color-mix(in oklab, red, blue 50%)', `mix → ${cssShim.mix('red', 'blue')}`)
 })
 
 console.log('\n── v4: check-platform.js ──────────────────────────────────────')
@@ -6,6 +6,16 @@ import type { Theme } from './theme'
  */
 type CSSValue = string | number
 
+/**
+ * A value per theme breakpoint, mobile-first: an array (base value, then one
+ * per breakpoint; `null` skips one) or an object keyed by `base` and
+ * breakpoint names.
+ *
+ * @example
+ * css({ padding: [8, 16, 24], gap: { base: 8, lg: 24 } })
+ */
+type ResponsiveValue = (CSSValue | null)[] | { base?: CSSValue; [breakpoint: string]: CSSValue | undefined }
+
 /**
  * A recursive map of CSS properties and nested rules.
  *
@@ -20,6 +30,9 @@ type CSSValue = string | number
  *   color: 'red',
  *   '&:hover': { color: 'darkred' },
  *   '@media (max-width: 600px)': { fontSize: 14 },
+ *   '@md': { fontSize: 18 }, // theme.breakpoints shorthand
+ *   _hover: { color: 'darkred' }, // condition shorthand
+ *   textStyle: 'heading.lg',       // composite token from theme.textStyles
  *   '@container sidebar (max-width: 300px)': { display: 'none' },
  * })
  * ```
@@ -28,10 +41,10 @@ type CSSValue = string | number
  * The Vite plugin will throw if it encounters a runtime variable.
  */
 type CSSProperties = {
-  [Property in keyof CSSStyleDeclaration]?: CSSValue
+  [Property in keyof CSSStyleDeclaration]?: CSSValue | ResponsiveValue
 } & {
   /** Nested selectors (e.g. `'&:hover'`), at-rules, and container queries. */
-  [key: string]: CSSValue | CSSProperties
+  [key: string]: CSSValue | ResponsiveValue | CSSProperties
 }
 
 // ---------------------------------------------------------------------------
@@ -68,8 +81,8 @@ type StyleFactory = (arg: ThemeArg) => CSSProperties
  * At **runtime** (Jest, Vitest in node mode, `ts-node`) the shim in
  * `src/css.ts` is used instead, which returns `''`.
  *
- * @param styles - A static object of CSS properties, or a theme factory
- *   function `({ theme }) => ({ ... })`.
+ * @param styles - Static objects of CSS properties, theme factory functions
+ *   `({ theme }) => ({ ... })` or other `css()` classes, deep-merged in order.
  * @returns The hashed class name string (at build time) or `''` (at runtime).
  *
  * @example
@@ -87,8 +100,105 @@ type StyleFactory = (arg: ThemeArg) => CSSProperties
  *   color: theme.colors.primary,
  *   fontFamily: theme.typography.fontFamily,
  * }))
+ *
+ * @example
+ * // Composition — later styles win, nested rules are merged
+ * const danger = css(button, isCompact && compact, { color: 'red' })
+ */
+export declare function css(...styles: Style[]): string
+
+/**
+ * One argument of a composed `css()` call: a style object, a theme factory,
+ * a `css()` class binding, an array of these, or a falsy value that
+ * contributes nothing.
+ */
+type Style = CSSProperties | StyleFactory | string | false | null | undefined | readonly Style[]
+
+/**
+ * Compose `css()` classes. When every argument is a static `css()` class or
+ * style, the call is replaced at build time by one class with their styles
+ * deep-merged in order, later arguments winning. Otherwise the class names
+ * are joined at runtime; falsy values are ignored.
+ *
+ * @example
+ * const submit = cx(button, primary)            // → one merged class
+ * const tab = cx(button, isActive && active)    // → joined at runtime
+ */
+export declare function cx(...args: Style[]): string
+
+// ---------------------------------------------------------------------------
+// recipe()
+// ---------------------------------------------------------------------------
+
+/** Variant groups: each group maps its values to styles. */
+type VariantGroups = Record<string, Record<string, Style>>
+
+/** The value type of a variant group; `true`/`false` keys make a boolean. */
+type VariantValue<Values> = keyof Values extends 'true' | 'false' ? boolean : keyof Values
+
+/** The props a recipe's selector function accepts. */
+type VariantProps<V extends VariantGroups> = {
+  [Group in keyof V]?: VariantValue<V[Group]>
+}
+
+/**
+ * The variant props of a recipe, for component props.
+ *
+ * @example
+ * type ButtonProps = RecipeVariants<typeof button>
+ */
+export type RecipeVariants<Recipe> = Recipe extends (props?: infer Props) => string ? NonNullable<Props> : never
+
+interface RecipeConfig<V extends VariantGroups> {
+  base?: Style
+  variants?: V
+  /** Styles applied when every listed variant matches one of its values. */
+  compoundVariants?: Array<
+    { [Group in keyof V]?: VariantValue<V[Group]> | Array<VariantValue<V[Group]>> } & { css: Style }
+  >
+  defaultVariants?: VariantProps<V>
+}
+
+/**
+ * Define a component's styles as variants. At build time the base, every
+ * variant value and every compound variant become a class, and the call is
+ * replaced with a function that returns the classes for its props.
+ *
+ * @example
+ * const button = recipe({
+ *   base: { padding: 8 },
+ *   variants: {
+ *     size: { sm: { fontSize: 12 }, lg: { fontSize: 18 } },
+ *     disabled: { true: { opacity: 0.5 } },
+ *   },
+ *   compoundVariants: [{ size: 'lg', disabled: true, css: { opacity: 0.3 } }],
+ *   defaultVariants: { size: 'sm' },
+ * })
+ * button({ size: 'lg' })   // → "cls_… cls_…"
+ */
+export declare function recipe<V extends VariantGroups = {}>(
+  config: RecipeConfig<V> | ((arg: ThemeArg) => RecipeConfig<V>)
+): (props?: VariantProps<V>) => string
+
+/**
+ * Define a CSS class from a block of plain CSS — declarations and nested
+ * rules, with `&` standing for the generated class.
+ *
+ * Interpolations may be constants, theme tokens, theme callbacks,
+ * `keyframes` bindings (their animation name) and `css()` bindings (their
+ * class selector).
+ *
+ * @example
+ * const button = css`
+ *   color: ${theme.colors.primary};
+ *   padding: 8px 16px;
+ *   &:hover { animation: ${pulse} 1s; }
+ * `
  */
-export declare function css(styles: CSSProperties | StyleFactory): string
+export declare function css(
+  strings: TemplateStringsArray,
+  ...values: Array<string | number | ((arg: ThemeArg) => string | number)>
+): string
 
 // ---------------------------------------------------------------------------
 // globalCss
@@ -115,15 +225,44 @@ export declare function globalCss(
   ...values: Array<string | number>
 ): void
 
+/**
+ * Global style rules: selectors and at-rules mapped to style objects.
+ */
+type GlobalStyles = {
+  [selector: string]: CSSProperties
+}
+
+/**
+ * Inject global CSS from an object of rules, or a theme factory returning
+ * one. Values are serialized like `css()` style objects; top-level keys are
+ * selectors or at-rules rather than rules nested under a class.
+ *
+ * @example
+ * globalCss(({ theme }) => ({
+ *   'html, body': { margin: 0, fontFamily: theme.typography.fontFamily },
+ *   ':root': { '--color-primary': theme.colors.primary },
+ * }))
+ */
+export declare function globalCss(styles: GlobalStyles | ((arg: ThemeArg) => GlobalStyles)): void
+
 // ---------------------------------------------------------------------------
 // keyframes
 // ---------------------------------------------------------------------------
 
+/**
+ * The frames of a `keyframes()` object: selectors such as `from`, `to` or
+ * `'50%'` mapped to the declarations at that point of the animation.
+ */
+type Keyframes = {
+  [selector: string]: CSSProperties
+}
+
 /**
  * Define a CSS `@keyframes` animation at build time.
  *
  * Returns the hashed animation name string — e.g. `"kf_a3f9b2c1"` — which
- * can be interpolated into `css()` string values:
+ * can be used as an `animationName` value or interpolated into `css()` string
+ * values:
  *
  * ```ts
  * const fadeIn = keyframes`
@@ -133,12 +272,30 @@ export declare function globalCss(
  * const el = css({ animation: `${fadeIn} 0.5s ease-out` })
  * ```
  *
+ * Interpolations may be constants, theme tokens or theme callbacks
+ * (`${({ theme }) => theme.colors.primary}`).
+ *
  * The `keyframes` declaration must textually precede any `css()` call that
  * references it in the same file.
  */
 export declare function keyframes(
   strings: TemplateStringsArray,
-  ...values: Array<string | number>
+  ...values: Array<string | number | ((arg: ThemeArg) => string | number)>
+): string
+
+/**
+ * Define a CSS `@keyframes` animation from an object of frames, serialized
+ * like a `css()` style object, or from a theme factory returning one.
+ *
+ * @example
+ * const fadeIn = keyframes({
+ *   from: { opacity: 0 },
+ *   to: { opacity: 1 },
+ * })
+ * const el = css({ animationName: fadeIn, animationDuration: '200ms' })
+ */
+export declare function keyframes(
+  frames: Keyframes | ((arg: ThemeArg) => Keyframes)
 ): string
 
 // ---------------------------------------------------------------------------
@@ -164,4 +321,101 @@ type ContainerType = 'size' | 'inline-size' | 'block-size' | 'normal'
  * })
  */
 export declare function container(name: string, type: ContainerType): CSSProperties
-export declare function container(type: ContainerType): CSSProperties
\ No newline at end of file
+export declare function container(type: ContainerType): CSSProperties
+// ---------------------------------------------------------------------------
+// Compile-time helpers
+// ---------------------------------------------------------------------------
+
+/**
+ * Fallback values for one property: one declaration per value, in order, so
+ * browsers that reject a later value keep an earlier one.
+ *
+ * @example
+ * css({ height: fallback('100vh', '100dvh') })
+ * // height: 100vh; height: 100dvh;
+ */
+export declare function fallback(...values: CSSValue[]): CSSValue
+
+/**
+ * Append `px` to a number. Evaluated at build time inside `css()` values.
+ *
+ * @example
+ * css(({ theme }) => ({ padding: px(theme.spacing.unit * 2) })) // "16px"
+ */
+export declare function px(value: number): string
+
+/**
+ * Convert a pixel size to `rem`, assuming a 16px root font size.
+ *
+ * @example
+ * css({ fontSize: rem(14) }) // "0.875rem"
+ */
+export declare function rem(px: number): string
+
+/**
+ * Convert a pixel size to `em` relative to `base` (default 16).
+ *
+ * @example
+ * css({ letterSpacing: em(1, 20) }) // "0.05em"
+ */
+export declare function em(px: number, base?: number): string
+
+/**
+ * Fluid size that scales linearly from `min` px at `minViewport` to `max` px
+ * at `maxViewport` (default 320–1280px), emitted as a `clamp()` in `rem`.
+ *
+ * @example
+ * css({ fontSize: fluid(14, 20) })
+ * // "clamp(0.875rem, 0.75rem + 0.625vw, 1.25rem)"
+ */
+export declare function fluid(
+  min: number,
+  max: number,
+  minViewport?: number,
+  maxViewport?: number
+): string
+
+// ---------------------------------------------------------------------------
+// Colour functions
+// ---------------------------------------------------------------------------
+
+/**
+ * Reduce a colour's OKLCH lightness by `amount` (0–1). Any CSS colour syntax
+ * is accepted; evaluated at build time inside `css()` values.
+ *
+ * @example
+ * css(({ theme }) => ({ '&:hover': { background: darken(theme.colors.primary, 0.1) } }))
+ */
+export declare function darken(color: string, amount: number): string
+
+/**
+ * Increase a colour's OKLCH lightness by `amount` (0–1).
+ *
+ * @example
+ * css({ background: lighten('#3366ff', 0.1) })
+ */
+export declare function lighten(color: string, amount: number): string
+
+/**
+ * Set a colour's opacity (0–1).
+ *
+ * @example
+ * css({ background: alpha('#3366ff', 0.5) }) // "#3366ff80"
+ */
+export declare function alpha(color: string, opacity: number): string
+
+/**
+ * Mix `weight` (0–1, default 0.5) of `b` into `a`, interpolated in OKLab.
+ *
+ * @example
+ * css({ borderColor: mix('red', 'blue', 0.3) })
+ */
+export declare function mix(a: string, b: string, weight?: number): string
+
+/**
+ * Black or white, whichever contrasts more with `background` (WCAG 2).
+ *
+ * @example
+ * css(({ theme }) => ({ color: contrastText(theme.colors.primary) }))
+ */
+export declare function contrastText(background: string): string
@@ -26,6 +26,11 @@ export interface TypographyTokens {
   [key: string]: CSSValue | undefined
 }
 
+/** A composite token: declarations (values may be lists) or nested presets. */
+export interface StyleToken {
+  [key: string]: CSSValue | CSSValue[] | StyleToken
+}
+
 export interface ColorSchemeVariant {
   colors?: ColorTokens
   [key: string]: Record<string, CSSValue> | undefined
@@ -43,6 +48,25 @@ export interface Theme {
   spacing?: SpacingTokens
   /** Typography tokens — e.g. `{ fontFamily: 'Inter, sans-serif' }` */
   typography?: TypographyTokens
+  /**
+   * Viewport widths for the `'@md'`, `'@md-down'` and `'@between md lg'`
+   * shorthand keys, smallest first — e.g. `{ sm: 640, md: 768, lg: '64rem' }`.
+   * Numbers are px.
+   */
+  breakpoints?: Record<string, CSSValue>
+  /**
+   * Typography presets for the `textStyle` key — e.g.
+   * `{ heading: { lg: { fontSize: 32, lineHeight: 1.2 } } }` is
+   * `textStyle: 'heading.lg'`.
+   */
+  textStyles?: Record<string, StyleToken>
+  /** Surface presets for the `layerStyle` key, like `textStyles`. */
+  layerStyles?: Record<string, StyleToken>
+  /**
+   * Extra or replacement condition keys for `css()`, mapped to a selector
+   * (with `&`) or an at-rule — e.g. `{ _groupHover: '.group:hover &' }`.
+   */
+  conditions?: Record<`_${string}`, string>
   /**
    * Named colour schemes, each with optional `light` and `dark` variants.
    * The plugin emits CSS custom-property blocks scoped to